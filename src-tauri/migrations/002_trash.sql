@@ -0,0 +1,14 @@
+create table if not exists trash_items (
+    id integer primary key autoincrement,
+    base_dir text not null,
+    original_path text not null,
+    name text not null,
+    trash_name text not null,
+    is_directory boolean not null,
+    file_count integer not null,
+    file_size integer not null,
+    deleted_at datetime not null default current_timestamp
+);
+
+create index if not exists idx_trash_items_base_dir on trash_items(base_dir);
+create index if not exists idx_trash_items_deleted_at on trash_items(deleted_at);
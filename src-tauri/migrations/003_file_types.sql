@@ -0,0 +1,57 @@
+-- 新增了Audio/Text/Document/Archive类型，并补充了一批图片和视频扩展名，
+-- 把之前按旧规则归为Other的收藏文件按扩展名重新归类；
+-- ts既可能是视频也可能是TypeScript源码，需要读取文件头才能区分，这里不做处理
+update favorite_files set file_type = 'Image'
+where file_type = 'Other' and (
+    lower(file_path) like '%.webp' or lower(file_path) like '%.avif' or
+    lower(file_path) like '%.heic' or lower(file_path) like '%.heif' or
+    lower(file_path) like '%.tif' or lower(file_path) like '%.tiff' or
+    lower(file_path) like '%.ico'
+);
+
+update favorite_files set file_type = 'Video'
+where file_type = 'Other' and (
+    lower(file_path) like '%.webm' or lower(file_path) like '%.m4v' or
+    lower(file_path) like '%.mpg' or lower(file_path) like '%.mpeg' or
+    lower(file_path) like '%.m2ts' or lower(file_path) like '%.mts' or
+    lower(file_path) like '%.rmvb'
+);
+
+update favorite_files set file_type = 'Audio'
+where file_type = 'Other' and (
+    lower(file_path) like '%.mp3' or lower(file_path) like '%.flac' or
+    lower(file_path) like '%.wav' or lower(file_path) like '%.aac' or
+    lower(file_path) like '%.m4a' or lower(file_path) like '%.ogg' or
+    lower(file_path) like '%.opus' or lower(file_path) like '%.wma' or
+    lower(file_path) like '%.ape'
+);
+
+update favorite_files set file_type = 'Text'
+where file_type = 'Other' and (
+    lower(file_path) like '%.txt' or lower(file_path) like '%.md' or
+    lower(file_path) like '%.log' or lower(file_path) like '%.json' or
+    lower(file_path) like '%.xml' or lower(file_path) like '%.yaml' or
+    lower(file_path) like '%.yml' or lower(file_path) like '%.toml' or
+    lower(file_path) like '%.ini' or lower(file_path) like '%.csv' or
+    lower(file_path) like '%.srt' or lower(file_path) like '%.ass' or
+    lower(file_path) like '%.vtt' or lower(file_path) like '%.lrc'
+);
+
+update favorite_files set file_type = 'Document'
+where file_type = 'Other' and (
+    lower(file_path) like '%.pdf' or lower(file_path) like '%.doc' or
+    lower(file_path) like '%.docx' or lower(file_path) like '%.xls' or
+    lower(file_path) like '%.xlsx' or lower(file_path) like '%.ppt' or
+    lower(file_path) like '%.pptx' or lower(file_path) like '%.odt' or
+    lower(file_path) like '%.ods' or lower(file_path) like '%.odp' or
+    lower(file_path) like '%.epub' or lower(file_path) like '%.rtf'
+);
+
+update favorite_files set file_type = 'Archive'
+where file_type = 'Other' and (
+    lower(file_path) like '%.zip' or lower(file_path) like '%.rar' or
+    lower(file_path) like '%.7z' or lower(file_path) like '%.tar' or
+    lower(file_path) like '%.gz' or lower(file_path) like '%.tgz' or
+    lower(file_path) like '%.bz2' or lower(file_path) like '%.xz' or
+    lower(file_path) like '%.zst'
+);
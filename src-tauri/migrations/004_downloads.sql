@@ -0,0 +1,16 @@
+create table if not exists downloads (
+    id integer primary key autoincrement,
+    online boolean not null,
+    -- 在线模式下为服务器地址，离线模式下为base_dir
+    source text not null,
+    path text not null,
+    filename text not null,
+    status text not null,
+    downloaded_bytes integer not null default 0,
+    total_bytes integer,
+    error text,
+    created_at datetime not null default current_timestamp,
+    updated_at datetime not null default current_timestamp
+);
+
+create index if not exists idx_downloads_status on downloads(status);
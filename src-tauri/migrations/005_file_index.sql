@@ -0,0 +1,32 @@
+create table if not exists file_index (
+    id integer primary key autoincrement,
+    base_dir text not null,
+    path text not null,
+    name text not null,
+    is_directory boolean not null,
+    size integer not null,
+    last_modified integer not null,
+    file_type text not null,
+    unique (base_dir, path)
+);
+
+-- trigram分词支持任意子串匹配，对中文文件名同样有效
+create virtual table if not exists file_index_fts using fts5(
+    name,
+    content = 'file_index',
+    content_rowid = 'id',
+    tokenize = 'trigram'
+);
+
+create trigger if not exists file_index_after_insert after insert on file_index begin
+    insert into file_index_fts(rowid, name) values (new.id, new.name);
+end;
+
+create trigger if not exists file_index_after_delete after delete on file_index begin
+    insert into file_index_fts(file_index_fts, rowid, name) values ('delete', old.id, old.name);
+end;
+
+create trigger if not exists file_index_after_update after update of name on file_index begin
+    insert into file_index_fts(file_index_fts, rowid, name) values ('delete', old.id, old.name);
+    insert into file_index_fts(rowid, name) values (new.id, new.name);
+end;
@@ -0,0 +1,17 @@
+create table if not exists tags (
+    id integer primary key autoincrement,
+    name text not null unique collate nocase,
+    created_at datetime not null default current_timestamp
+);
+
+-- 标签保存在本机，source在线模式下为服务器地址，离线模式下为base_dir
+create table if not exists file_tags (
+    tag_id integer not null,
+    source text not null,
+    path text not null,
+    created_at datetime not null default current_timestamp,
+    primary key (tag_id, source, path),
+    foreign key (tag_id) references tags(id) on delete cascade
+);
+
+create index if not exists idx_file_tags_path on file_tags(source, path);
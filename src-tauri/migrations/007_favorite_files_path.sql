@@ -0,0 +1,2 @@
+-- 文件列表需要按路径查找所在的收藏夹
+create index if not exists idx_favorite_files_file_path on favorite_files(file_path);
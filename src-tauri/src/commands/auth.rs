@@ -0,0 +1,33 @@
+use tauri::command;
+
+use crate::commands::config::{is_online, with_config};
+use crate::models::auth::{AuthStatus, LoginRequest};
+use crate::models::error::ApiError;
+use crate::services::auth::AuthManager;
+
+fn current_server_url() -> Result<String, ApiError> {
+    if !is_online() {
+        return Err(ApiError::new(400, "离线模式不需要登录".to_string()));
+    }
+    Ok(with_config(|config| config.server_url.clone()))
+}
+
+/// 登录当前服务器，令牌保存到本地，之后的请求自动附带
+#[command]
+pub async fn login(request: LoginRequest) -> Result<AuthStatus, ApiError> {
+    if request.username.trim().is_empty() {
+        return Err(ApiError::new(400, "用户名不能为空".to_string()));
+    }
+    AuthManager::login(&current_server_url()?, &request).await
+}
+
+#[command]
+pub async fn logout() -> Result<(), ApiError> {
+    AuthManager::logout(&current_server_url()?).await
+}
+
+/// 当前服务器的登录状态，离线模式下为未登录
+#[command]
+pub fn get_auth_status() -> AuthStatus {
+    AuthManager::status(&with_config(|config| config.server_url.clone()))
+}
@@ -2,24 +2,131 @@ use std::sync::{Arc, RwLock};
 
 use lazy_static::lazy_static;
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 use tauri::{command, AppHandle, Manager};
+use tauri_plugin_store::StoreExt;
 
-use crate::{models::error::ApiError, repos::offline::Database};
+use crate::{
+    commands::{
+        files::clear_file_details_cache,
+        thumbnail::{apply_thumbnail_limits, clear_thumbnail_cache},
+    },
+    models::{
+        config_check::ConfigCheckResult,
+        connectivity::ConnectivityStatus,
+        error::ApiError,
+        profile::{ProfileKind, ServerProfile},
+    },
+    repos::offline::{directory_watcher::DirectoryWatcher, Database},
+    services::{
+        config_validator::ConfigValidator, connectivity::ConnectivityMonitor,
+        download_queue::DownloadQueue, private_store::PrivateStore,
+    },
+};
+
+// 与前端共用同一个store文件和键
+const CONFIG_STORE_PATH: &str = ".settings.dat";
+const CONFIG_STORE_KEY: &str = "app-config";
+/// 配置结构的当前版本，新增需要迁移的字段时加一并在migrate_config中补充对应的步骤
+const CONFIG_SCHEMA_VERSION: u32 = 3;
+// 早期版本写死的默认目录
+const LEGACY_DEFAULT_BASE_DIR: &str = "E:/ZTEMP/kukuku";
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct AppConfig {
+    /// 保存时写入，旧版本保存的配置没有这个字段，按0处理
+    #[serde(default)]
+    pub schema_version: u32,
     pub online: bool,
     pub server_url: String,
     pub base_dir: String,
+    /// 离线回收站中条目的保留天数，0表示不自动清理
+    #[serde(default = "default_trash_retention_days")]
+    pub trash_retention_days: u32,
+    /// 下载队列中同时进行的下载数
+    #[serde(default = "default_max_concurrent_downloads")]
+    pub max_concurrent_downloads: u32,
+    /// 同时生成或下载缩略图的最大数量，有效范围1~32
+    #[serde(default = "default_thumbnail_max_concurrent")]
+    pub thumbnail_max_concurrent: u32,
+    /// 缩略图缓存的最大条目数，有效范围1~10000
+    #[serde(default = "default_thumbnail_cache_entries")]
+    pub thumbnail_cache_entries: u32,
+    /// 缩略图缓存占用的最大字节数，与条目数限制同时生效，有效范围1MiB~1GiB
+    #[serde(default = "default_thumbnail_cache_bytes")]
+    pub thumbnail_cache_bytes: u64,
+    /// 已保存的连接配置
+    #[serde(default)]
+    pub profiles: Vec<ServerProfile>,
+    /// 当前激活的连接配置，手动修改online、server_url或base_dir后不再对应任何配置时为空
+    #[serde(default)]
+    pub active_profile_id: Option<String>,
+    /// 服务器不可用时只读操作自动改用离线镜像或缓存
+    #[serde(default)]
+    pub auto_fallback: bool,
+    /// 与服务器目录结构相同的本地目录，如定期同步的备份
+    #[serde(default)]
+    pub offline_mirror_dir: Option<String>,
+}
+
+fn default_trash_retention_days() -> u32 {
+    30
+}
+
+fn default_max_concurrent_downloads() -> u32 {
+    3
+}
+
+fn default_thumbnail_max_concurrent() -> u32 {
+    5
+}
+
+fn default_thumbnail_cache_entries() -> u32 {
+    200
+}
+
+fn default_thumbnail_cache_bytes() -> u64 {
+    64 * 1024 * 1024
+}
+
+/// 安卓模拟器中通过10.0.2.2访问宿主机
+fn default_server_url() -> String {
+    if cfg!(target_os = "android") {
+        "http://10.0.2.2:8080".to_string()
+    } else {
+        "http://localhost:8080".to_string()
+    }
+}
+
+/// 依次尝试系统的视频、图片和用户目录
+fn default_base_dir() -> String {
+    if cfg!(target_os = "android") {
+        return "/storage/emulated/0".to_string();
+    }
+    dirs::video_dir()
+        .or_else(dirs::picture_dir)
+        .or_else(dirs::home_dir)
+        .map(|dir| dir.to_string_lossy().into_owned())
+        .unwrap_or_default()
 }
 
 impl Default for AppConfig {
     fn default() -> Self {
         Self {
+            schema_version: CONFIG_SCHEMA_VERSION,
             online: true,
-            server_url: "http://localhost:8080".to_string(),
-            base_dir: "E:/ZTEMP/kukuku".to_string(),
+            server_url: default_server_url(),
+            base_dir: default_base_dir(),
+            trash_retention_days: default_trash_retention_days(),
+            max_concurrent_downloads: default_max_concurrent_downloads(),
+            thumbnail_max_concurrent: default_thumbnail_max_concurrent(),
+            thumbnail_cache_entries: default_thumbnail_cache_entries(),
+            thumbnail_cache_bytes: default_thumbnail_cache_bytes(),
+            profiles: Vec::new(),
+            active_profile_id: None,
+            auto_fallback: false,
+            offline_mirror_dir: None,
         }
     }
 }
@@ -42,16 +149,268 @@ pub fn is_online() -> bool {
     with_config(|config| config.online)
 }
 
+/// 只读操作是否走在线仓库，服务器不可用并改用离线镜像时为false
+pub fn read_online() -> bool {
+    is_online() && !ConnectivityMonitor::using_mirror()
+}
+
+/// 只读操作使用的本地目录，改用离线镜像时为offline_mirror_dir，否则为base_dir
+pub fn read_base_dir() -> String {
+    let using_mirror = ConnectivityMonitor::using_mirror();
+    with_config(|config| match &config.offline_mirror_dir {
+        Some(mirror_dir) if using_mirror => mirror_dir.clone(),
+        _ => config.base_dir.clone(),
+    })
+}
+
+/// 本机数据所属的位置，在线模式下为服务器地址，离线模式下为base_dir
+pub fn current_source() -> String {
+    with_config(|config| {
+        if config.online {
+            config.server_url.clone()
+        } else {
+            config.base_dir.clone()
+        }
+    })
+}
+
 #[command]
 pub fn get_app_config() -> AppConfig {
     with_config(|config| config.clone())
 }
 
+/// 立即检查一次服务器是否可达
+#[command]
+pub async fn check_connectivity(app: AppHandle) -> ConnectivityStatus {
+    ConnectivityMonitor::check(&app).await
+}
+
+/// 逐项检查配置，不会修改当前配置
+#[command]
+pub async fn validate_app_config(config: AppConfig) -> ConfigCheckResult {
+    ConfigValidator::validate(&config).await
+}
+
+/// 配置检查不通过时拒绝保存，force为true时跳过检查
 #[command]
-pub fn set_app_config(config: AppConfig) {
+pub async fn set_app_config(
+    config: AppConfig,
+    force: Option<bool>,
+    app: AppHandle,
+) -> Result<(), ApiError> {
+    // 只改了缩略图、下载等与连接无关的设置时不需要检查服务器和目录
+    let connection_changed = with_config(|current| {
+        current.online != config.online
+            || current.server_url != config.server_url
+            || current.base_dir != config.base_dir
+    });
+    if connection_changed && !force.unwrap_or(false) {
+        let result = ConfigValidator::validate(&config).await;
+        if !result.valid {
+            return Err(ApiError::new(
+                400,
+                format!("配置无效: {}", result.error_message()),
+            ));
+        }
+    }
+    update_app_config(&app, |current_config| {
+        *current_config = config;
+        // 手动改动连接后，激活的配置不再代表当前连接
+        let still_applied = current_config
+            .active_profile_id
+            .as_ref()
+            .and_then(|id| current_config.profiles.iter().find(|p| &p.id == id))
+            .is_some_and(|profile| profile_applied(current_config, profile));
+        if !still_applied {
+            current_config.active_profile_id = None;
+        }
+        Ok(())
+    })?;
+    if connection_changed {
+        reset_connection_state(&app).await?;
+    }
+    // 并发数或服务器变化后，队列中的下载可能需要开始
+    DownloadQueue::schedule();
+    apply_thumbnail_limits(&app).await;
+    Ok(())
+}
+
+/// 换了服务器或目录后清空以路径为键的缓存，同一路径可能已经是另一个文件；
+/// 监听的也是旧目录下的路径，由前端重新监听
+pub async fn reset_connection_state(app: &AppHandle) -> Result<(), ApiError> {
+    clear_thumbnail_cache(app.clone()).await?;
+    clear_file_details_cache().await;
+    DirectoryWatcher::unwatch();
+    Ok(())
+}
+
+/// 在当前配置的副本上修改并保存，保存成功后才替换内存中的配置，返回修改后的配置
+pub fn update_app_config(
+    app: &AppHandle,
+    f: impl FnOnce(&mut AppConfig) -> Result<(), ApiError>,
+) -> Result<AppConfig, ApiError> {
+    let mut config = with_config(|config| config.clone());
+    f(&mut config)?;
+    config.schema_version = CONFIG_SCHEMA_VERSION;
+    save_app_config(app, &config)?;
+    with_config_mut(|current_config| {
+        *current_config = config.clone();
+    });
+    Ok(config)
+}
+
+/// 配置当前的连接是否就是profile描述的连接
+fn profile_applied(config: &AppConfig, profile: &ServerProfile) -> bool {
+    match profile.kind {
+        ProfileKind::Server => config.online && config.server_url == profile.location,
+        ProfileKind::Local => !config.online && config.base_dir == profile.location,
+    }
+}
+
+/// 启动时从store读取配置，没有保存过时使用默认值且不写入，以便前端判断是否首次启动
+pub fn load_app_config(app: &AppHandle) -> Result<(), ApiError> {
+    let store = app
+        .store(CONFIG_STORE_PATH)
+        .map_err(|e| ApiError::new(500, format!("打开配置文件失败: {}", e)))?;
+    let Some(mut value) = store.get(CONFIG_STORE_KEY) else {
+        return Ok(());
+    };
+
+    let migrated = migrate_config(&mut value);
+    let config: AppConfig = serde_json::from_value(value)
+        .map_err(|e| ApiError::new(500, format!("解析配置失败: {}", e)))?;
+    if migrated {
+        save_app_config(app, &config)?;
+    }
     with_config_mut(|current_config| {
         *current_config = config;
     });
+    Ok(())
+}
+
+fn save_app_config(app: &AppHandle, config: &AppConfig) -> Result<(), ApiError> {
+    let store = app
+        .store(CONFIG_STORE_PATH)
+        .map_err(|e| ApiError::new(500, format!("打开配置文件失败: {}", e)))?;
+    let value = serde_json::to_value(config)
+        .map_err(|e| ApiError::new(500, format!("序列化配置失败: {}", e)))?;
+    store.set(CONFIG_STORE_KEY, value);
+    store
+        .save()
+        .map_err(|e| ApiError::new(500, format!("保存配置失败: {}", e)))
+}
+
+/// 把旧版本的配置逐步升级到当前版本，返回是否做了修改；新增的普通字段由serde默认值补全
+fn migrate_config(value: &mut Value) -> bool {
+    let Some(object) = value.as_object_mut() else {
+        return false;
+    };
+    let version = object
+        .get("schemaVersion")
+        .and_then(Value::as_u64)
+        .unwrap_or(0) as u32;
+    if version >= CONFIG_SCHEMA_VERSION {
+        return false;
+    }
+
+    if version < 1 {
+        // 0 -> 1: 旧版本的默认目录在大多数机器上不存在，换成当前平台的默认目录
+        let is_legacy_default = object
+            .get("baseDir")
+            .and_then(Value::as_str)
+            .is_some_and(|dir| dir == LEGACY_DEFAULT_BASE_DIR)
+            && !std::path::Path::new(LEGACY_DEFAULT_BASE_DIR).is_dir();
+        if is_legacy_default {
+            object.insert("baseDir".to_string(), Value::from(default_base_dir()));
+        }
+    }
+
+    if version < 2 {
+        // 1 -> 2: 把原有的服务器地址和base_dir各保存为一个连接配置，并激活当前使用的那个
+        migrate_profiles(object);
+    }
+
+    if version < 3 {
+        // 2 -> 3: 连接配置中的明文密码移到私有文件，配置里只保留是否保存了密码
+        migrate_profile_passwords(object);
+    }
+
+    object.insert(
+        "schemaVersion".to_string(),
+        Value::from(CONFIG_SCHEMA_VERSION),
+    );
+    true
+}
+
+fn migrate_profiles(object: &mut serde_json::Map<String, Value>) {
+    let field = |key: &str| {
+        object
+            .get(key)
+            .and_then(Value::as_str)
+            .filter(|value| !value.is_empty())
+            .map(str::to_string)
+    };
+    let online = object.get("online").and_then(Value::as_bool);
+    let mut profiles = Vec::new();
+    let mut active_profile_id = None;
+    for (kind, name, location) in [
+        (ProfileKind::Server, "服务器", field("serverUrl")),
+        (ProfileKind::Local, "本地目录", field("baseDir")),
+    ] {
+        let Some(location) = location else {
+            continue;
+        };
+        let profile = ServerProfile {
+            id: uuid::Uuid::new_v4().to_string(),
+            name: name.to_string(),
+            kind,
+            location,
+            credentials: None,
+        };
+        if online == Some(kind == ProfileKind::Server) {
+            active_profile_id = Some(profile.id.clone());
+        }
+        profiles.push(profile);
+    }
+
+    if let Ok(profiles) = serde_json::to_value(profiles) {
+        object.insert("profiles".to_string(), profiles);
+        object.insert(
+            "activeProfileId".to_string(),
+            serde_json::to_value(active_profile_id).unwrap_or(Value::Null),
+        );
+    }
+}
+
+fn migrate_profile_passwords(object: &mut serde_json::Map<String, Value>) {
+    let Some(profiles) = object.get_mut("profiles").and_then(Value::as_array_mut) else {
+        return;
+    };
+    for profile in profiles {
+        let id = profile
+            .get("id")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string();
+        let Some(credentials) = profile
+            .get_mut("credentials")
+            .and_then(Value::as_object_mut)
+        else {
+            continue;
+        };
+        let password = credentials.remove("password");
+        let password = password
+            .as_ref()
+            .and_then(Value::as_str)
+            .filter(|password| !password.is_empty());
+        // 保存失败时丢弃密码，需要重新输入，也不能继续明文保存
+        let saved = password.is_some_and(|password| {
+            PrivateStore::set_profile_password(&id, Some(password))
+                .inspect_err(|e| eprintln!("迁移连接配置的密码失败: {}", e.message))
+                .is_ok()
+        });
+        credentials.insert("hasPassword".to_string(), Value::from(saved));
+    }
 }
 
 #[command]
@@ -0,0 +1,32 @@
+use tauri::command;
+
+use crate::{
+    models::{download::DownloadItemDto, error::ApiError},
+    services::download_queue::DownloadQueue,
+};
+
+/// 加入下载队列后立即返回，状态变化通过`download-status`事件推送
+#[command]
+pub async fn enqueue_download(path: String, filename: String) -> Result<DownloadItemDto, ApiError> {
+    DownloadQueue::enqueue(&path, &filename).await
+}
+
+#[command]
+pub async fn list_downloads() -> Result<Vec<DownloadItemDto>, ApiError> {
+    DownloadQueue::list().await
+}
+
+#[command]
+pub async fn pause_download(id: i64) -> Result<DownloadItemDto, ApiError> {
+    DownloadQueue::pause(id).await
+}
+
+#[command]
+pub async fn resume_download(id: i64) -> Result<DownloadItemDto, ApiError> {
+    DownloadQueue::resume(id).await
+}
+
+#[command]
+pub async fn cancel_download(id: i64) -> Result<DownloadItemDto, ApiError> {
+    DownloadQueue::cancel(id).await
+}
@@ -0,0 +1,58 @@
+use tauri::{command, AppHandle, Emitter};
+
+use crate::commands::config::is_online;
+use crate::commands::files::delete_file;
+use crate::models::duplicates::{DuplicateGroup, DuplicateProgress, DuplicateProgressPayload};
+use crate::models::error::ApiError;
+use crate::models::files::{TransferFailure, TransferResult};
+use crate::repos::offline::duplicate_finder::DuplicateFinder;
+use crate::utils::cancellation::{self, CancelToken};
+
+/// 在root（默认为根目录）下查找重复文件，进度通过`duplicate-progress`事件推送
+#[command]
+pub async fn find_duplicates(
+    task_id: String,
+    root: Option<String>,
+    app: AppHandle,
+) -> Result<Vec<DuplicateGroup>, ApiError> {
+    if is_online() {
+        return Err(ApiError::new(400, "在线模式不支持查找重复文件".to_string()));
+    }
+
+    let token = CancelToken::register(&task_id);
+    let root = root.unwrap_or_else(|| "/".to_string());
+    DuplicateFinder::find(&root, &token, |progress: &DuplicateProgress| {
+        let _ = app.emit(
+            "duplicate-progress",
+            DuplicateProgressPayload {
+                task_id: task_id.clone(),
+                progress: progress.clone(),
+            },
+        );
+    })
+    .await
+}
+
+#[command]
+pub fn cancel_find_duplicates(task_id: String) -> bool {
+    cancellation::cancel(&task_id)
+}
+
+/// 删除选中的重复文件，与`delete_file`一样默认移入回收站
+#[command]
+pub async fn delete_duplicates(
+    paths: Vec<String>,
+    permanent: Option<bool>,
+) -> Result<TransferResult, ApiError> {
+    let mut result = TransferResult::default();
+    for path in paths {
+        match delete_file(path.clone(), permanent).await {
+            Ok(_) => result.completed.push(path),
+            Err(e) => result.failed.push(TransferFailure {
+                path,
+                message: e.message,
+            }),
+        }
+    }
+    Ok(result)
+}
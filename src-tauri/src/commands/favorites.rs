@@ -6,6 +6,7 @@ use crate::models::favorite::{
     AddFileToFavoriteRequest, CreateFavoriteRequest, FavoriteDto, FavoriteFileDto,
     UpdateFavoriteRequest,
 };
+use crate::models::files::FileInfo;
 use crate::repos::favorites_repo::FavoritesRepo;
 use crate::repos::offline::offline_favorites_repo::OfflineFavoritesRepo;
 use crate::repos::online::online_favorites_repo::OnlineFavoritesRepo;
@@ -41,6 +42,37 @@ pub async fn get_all_favorite_files() -> Result<Vec<FavoriteFileDto>, ApiError>
     }
 }
 
+/// 包含该路径的收藏夹，每个收藏夹的files中只有该路径对应的条目
+#[command]
+pub async fn get_favorites_for_path(path: String) -> Result<Vec<FavoriteDto>, ApiError> {
+    if is_online() {
+        OnlineFavoritesRepo::get_favorites_for_path(&path).await
+    } else {
+        OfflineFavoritesRepo::get_favorites_for_path(&path).await
+    }
+}
+
+/// 把所在收藏夹的id填入文件列表，失败时保持列表不变
+pub async fn attach_favorite_ids(files: &mut [FileInfo]) {
+    if files.is_empty() {
+        return;
+    }
+    let paths: Vec<String> = files.iter().map(|file| file.path.clone()).collect();
+    let result = if is_online() {
+        OnlineFavoritesRepo::get_favorite_ids_for_paths(&paths).await
+    } else {
+        OfflineFavoritesRepo::get_favorite_ids_for_paths(&paths).await
+    };
+    match result {
+        Ok(mut ids) => {
+            for file in files {
+                file.favorite_ids = ids.remove(&file.path).unwrap_or_default();
+            }
+        }
+        Err(e) => eprintln!("读取收藏信息失败: {}", e.message),
+    }
+}
+
 #[command]
 pub async fn delete_favorite_file(id: i64) -> Result<bool, ApiError> {
     if is_online() {
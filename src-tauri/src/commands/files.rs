@@ -1,36 +1,398 @@
-use tauri::command;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
 
-use crate::commands::config::is_online;
-use crate::models::error::ApiError;
-use crate::models::files::FileInfo;
+use tauri::{command, AppHandle, Emitter};
+use tokio::sync::Mutex;
+
+use crate::commands::config::{is_online, read_base_dir, read_online, with_config};
+use crate::commands::favorites::attach_favorite_ids;
+use crate::models::error::{ApiError, ErrorType};
+use crate::models::file_details::FileDetails;
+use crate::models::files::{
+    DeleteSummary, DownloadProgress, FileInfo, FileListing, FolderDownloadRequest, ListOptions,
+    RenameRequest, SearchQuery, SearchResultPayload, TransferProgress, TransferProgressPayload,
+    TransferRequest, TransferResult, UploadRequest,
+};
+use crate::models::trash::TrashItemDto;
 use crate::repos::files_repo::FilesRepo;
+use crate::repos::offline::directory_watcher::DirectoryWatcher;
 use crate::repos::offline::offline_files_repo::OfflineFilesRepo;
+use crate::repos::offline::offline_trash_repo::OfflineTrashRepo;
+use crate::repos::offline::tag_store::TagStore;
+use crate::repos::offline::OfflineRepo;
 use crate::repos::online::online_files_repo::OnlineFilesRepo;
+use crate::repos::online::online_trash_repo::OnlineTrashRepo;
+use crate::repos::online::OnlineRepo;
+use crate::repos::trash_repo::TrashRepo;
 use crate::repos::Repo;
+use crate::utils::cancellation::{self, CancelToken};
+use crate::utils::lru_cache::LruCache;
+
+// 文件详情缓存，值中附带写入时间
+type FileDetailsCache = LruCache<String, (FileDetails, Instant)>;
+
+static FILE_DETAILS_CACHE: OnceLock<Mutex<FileDetailsCache>> = OnceLock::new();
+
+// 在线模式下无法廉价地判断文件是否变化，只按存活时间失效
+const ONLINE_FILE_DETAILS_TTL: Duration = Duration::from_secs(300);
+
+// 最近从服务器获取的目录列表，服务器不可用时作为只读的后备数据
+static ONLINE_LISTING_CACHE: OnceLock<Mutex<LruCache<String, FileListing>>> = OnceLock::new();
+
+fn get_file_details_cache() -> &'static Mutex<FileDetailsCache> {
+    FILE_DETAILS_CACHE.get_or_init(|| Mutex::new(LruCache::new(500)))
+}
+
+fn get_online_listing_cache() -> &'static Mutex<LruCache<String, FileListing>> {
+    ONLINE_LISTING_CACHE.get_or_init(|| Mutex::new(LruCache::new(200)))
+}
+
+/// 开启了自动降级且请求因网络原因失败，此时可以改用缓存
+fn can_fall_back(error: &ApiError) -> bool {
+    matches!(error.error_type, ErrorType::Network) && with_config(|config| config.auto_fallback)
+}
+
+/// 切换连接后路径指向的不再是同一个文件，需要清空
+pub async fn clear_file_details_cache() {
+    get_file_details_cache().lock().await.clear();
+    get_online_listing_cache().lock().await.clear();
+}
+
+#[command]
+pub async fn get_files(
+    path: String,
+    options: Option<ListOptions>,
+) -> Result<FileListing, ApiError> {
+    let options = options.unwrap_or_default();
+    let mut listing = if read_online() {
+        get_online_files(&path, &options).await?
+    } else {
+        OfflineFilesRepo::get_files(&path, &options).await?
+    };
+    TagStore::attach(&mut listing.files).await;
+    attach_favorite_ids(&mut listing.files).await;
+    Ok(listing)
+}
+
+/// 成功时写入缓存，服务器不可用时返回缓存中的列表
+async fn get_online_files(path: &str, options: &ListOptions) -> Result<FileListing, ApiError> {
+    let key = format!(
+        "{}{}?{}",
+        OnlineFilesRepo::get_server_url(),
+        path,
+        serde_json::to_string(options).unwrap_or_default()
+    );
+    match OnlineFilesRepo::get_files(path, options).await {
+        Ok(listing) => {
+            get_online_listing_cache()
+                .lock()
+                .await
+                .put(key, listing.clone());
+            Ok(listing)
+        }
+        Err(e) if can_fall_back(&e) => get_online_listing_cache().lock().await.get(&key).ok_or(e),
+        Err(e) => Err(e),
+    }
+}
+
+/// 默认移入回收站，服务器没有回收站时返回501；permanent为true时直接永久删除。
+/// 移入回收站的文件的标签会暂存到回收站条目下，还原时跟着回来；永久删除时一并删除
+#[command]
+pub async fn delete_file(path: String, permanent: Option<bool>) -> Result<bool, ApiError> {
+    if !permanent.unwrap_or(false) {
+        move_to_trash(&path, false).await?;
+        return Ok(true);
+    }
+
+    let deleted = if is_online() {
+        OnlineFilesRepo::delete(path.clone()).await?
+    } else {
+        OfflineFilesRepo::delete(path.clone()).await?
+    };
+    if deleted {
+        TagStore::forget(&path).await;
+    }
+    Ok(deleted)
+}
+
+/// 移入回收站，并把标签移到回收站条目下
+async fn move_to_trash(path: &str, recursive: bool) -> Result<TrashItemDto, ApiError> {
+    let item = if is_online() {
+        OnlineTrashRepo::move_to_trash(path, recursive)
+            .await
+            .map_err(OnlineTrashRepo::unsupported_hint)?
+    } else {
+        OfflineTrashRepo::move_to_trash(path, recursive).await?
+    };
+    TagStore::relocate(path, &TagStore::trash_path(item.id)).await;
+    Ok(item)
+}
+
+#[command]
+pub async fn create_directory(path: String) -> Result<FileInfo, ApiError> {
+    if is_online() {
+        OnlineFilesRepo::create_directory(&path).await
+    } else {
+        OfflineFilesRepo::create_directory(&path).await
+    }
+}
+
+/// 删除目录，非空目录只有在recursive为true时才会被删除；回收站、permanent和标签的处理同delete_file
+#[command]
+pub async fn delete_directory(
+    path: String,
+    recursive: bool,
+    permanent: Option<bool>,
+) -> Result<DeleteSummary, ApiError> {
+    if !permanent.unwrap_or(false) {
+        let trashed = move_to_trash(&path, recursive).await?;
+        return Ok(DeleteSummary {
+            removed_files: trashed.file_count as u64,
+            removed_bytes: trashed.file_size as u64,
+            failed: Vec::new(),
+        });
+    }
+
+    let summary = if is_online() {
+        OnlineFilesRepo::delete_directory(&path, recursive).await?
+    } else {
+        OfflineFilesRepo::delete_directory(&path, recursive).await?
+    };
+    TagStore::forget(&path).await;
+    Ok(summary)
+}
+
+/// 下载文件，进度通过`download-progress`事件推送
+#[command]
+pub async fn download_file(path: String, filename: String, app: AppHandle) -> Result<(), ApiError> {
+    let on_progress = |progress: &DownloadProgress| {
+        let _ = app.emit("download-progress", progress.clone());
+    };
+    if read_online() {
+        OnlineFilesRepo::download_file(&path, &filename, on_progress).await
+    } else {
+        OfflineFilesRepo::download_file(&path, &filename, on_progress).await
+    }
+}
+
+/// 递归搜索文件，找到的结果会通过`search-result`事件逐条推送；在线模式不支持，返回501
+#[command]
+pub async fn search_files(
+    search_id: String,
+    query: SearchQuery,
+    app: AppHandle,
+) -> Result<Vec<FileInfo>, ApiError> {
+    let token = CancelToken::register(&search_id);
+    let on_found = |file: &FileInfo| {
+        let _ = app.emit(
+            "search-result",
+            SearchResultPayload {
+                search_id: search_id.clone(),
+                file: file.clone(),
+            },
+        );
+    };
+
+    if read_online() {
+        OnlineFilesRepo::search(&query, &token, on_found).await
+    } else {
+        OfflineFilesRepo::search(&query, &token, on_found).await
+    }
+}
+
+#[command]
+pub fn cancel_search(search_id: String) -> bool {
+    cancellation::cancel(&search_id)
+}
 
 #[command]
-pub async fn get_files(path: String) -> Result<Vec<FileInfo>, ApiError> {
+pub async fn rename_file(request: RenameRequest) -> Result<String, ApiError> {
+    let new_path = if is_online() {
+        OnlineFilesRepo::rename(&request).await?
+    } else {
+        OfflineFilesRepo::rename(&request).await?
+    };
+    TagStore::relocate(&request.path, &new_path).await;
+    Ok(new_path)
+}
+
+/// 移动文件，进度通过`transfer-progress`事件推送
+#[command]
+pub async fn move_files(
+    task_id: String,
+    request: TransferRequest,
+    app: AppHandle,
+) -> Result<TransferResult, ApiError> {
+    let on_progress = |progress: &TransferProgress| {
+        emit_transfer_progress(&app, "transfer-progress", &task_id, progress)
+    };
+    let result = if is_online() {
+        OnlineFilesRepo::move_files(&request, on_progress).await?
+    } else {
+        OfflineFilesRepo::move_files(&request, on_progress).await?
+    };
+    for (from, to) in moved_paths(&request, &result) {
+        TagStore::relocate(from, to).await;
+    }
+    Ok(result)
+}
+
+/// 成功移动的源路径与completed中的新路径按顺序一一对应
+fn moved_paths<'a>(
+    request: &'a TransferRequest,
+    result: &'a TransferResult,
+) -> impl Iterator<Item = (&'a String, &'a String)> {
+    request
+        .sources
+        .iter()
+        .filter(|source| {
+            !result.skipped.contains(source) && !result.failed.iter().any(|f| &f.path == *source)
+        })
+        .zip(&result.completed)
+}
+
+/// 复制文件，进度通过`transfer-progress`事件推送
+#[command]
+pub async fn copy_files(
+    task_id: String,
+    request: TransferRequest,
+    app: AppHandle,
+) -> Result<TransferResult, ApiError> {
+    let on_progress = |progress: &TransferProgress| {
+        emit_transfer_progress(&app, "transfer-progress", &task_id, progress)
+    };
     if is_online() {
-        OnlineFilesRepo::get_files(&path).await
+        OnlineFilesRepo::copy_files(&request, on_progress).await
     } else {
-        OfflineFilesRepo::get_files(&path).await
+        OfflineFilesRepo::copy_files(&request, on_progress).await
     }
 }
 
+/// 上传文件或目录，进度通过`upload-progress`事件推送，可用`cancel_upload`中止
 #[command]
-pub async fn delete_file(path: String) -> Result<bool, ApiError> {
+pub async fn upload_files(
+    task_id: String,
+    request: UploadRequest,
+    app: AppHandle,
+) -> Result<TransferResult, ApiError> {
+    let token = CancelToken::register(&task_id);
+    let on_progress = |progress: &TransferProgress| {
+        emit_transfer_progress(&app, "upload-progress", &task_id, progress)
+    };
     if is_online() {
-        OnlineFilesRepo::delete(path).await
+        OnlineFilesRepo::upload(&request, &token, on_progress).await
+    } else {
+        OfflineFilesRepo::upload(&request, &token, on_progress).await
+    }
+}
+
+#[command]
+pub fn cancel_upload(task_id: String) -> bool {
+    cancellation::cancel(&task_id)
+}
+
+/// 下载整个目录，进度通过`folder-download-progress`事件推送，可用`cancel_folder_download`中止
+#[command]
+pub async fn download_folder(
+    task_id: String,
+    request: FolderDownloadRequest,
+    app: AppHandle,
+) -> Result<String, ApiError> {
+    let token = CancelToken::register(&task_id);
+    let on_progress = |progress: &TransferProgress| {
+        emit_transfer_progress(&app, "folder-download-progress", &task_id, progress)
+    };
+    if read_online() {
+        OnlineFilesRepo::download_folder(&request, &token, on_progress).await
     } else {
-        OfflineFilesRepo::delete(path).await
+        OfflineFilesRepo::download_folder(&request, &token, on_progress).await
     }
 }
 
 #[command]
-pub async fn download_file(path: String, filename: String) -> Result<(), ApiError> {
+pub fn cancel_folder_download(task_id: String) -> bool {
+    cancellation::cancel(&task_id)
+}
+
+fn emit_transfer_progress(
+    app: &AppHandle,
+    event: &str,
+    task_id: &str,
+    progress: &TransferProgress,
+) {
+    let _ = app.emit(
+        event,
+        TransferProgressPayload {
+            task_id: task_id.to_string(),
+            progress: progress.clone(),
+        },
+    );
+}
+
+/// 监听离线模式下当前打开的目录，变更通过`files-changed`事件推送
+#[command]
+pub fn watch_directory(path: String, app: AppHandle) -> Result<(), ApiError> {
     if is_online() {
-        OnlineFilesRepo::download_file(&path, &filename).await
+        return Err(ApiError::new(400, "在线模式不支持目录监听".to_string()));
+    }
+    DirectoryWatcher::watch(&path, &app)
+}
+
+#[command]
+pub fn unwatch_directory() -> bool {
+    DirectoryWatcher::unwatch()
+}
+
+/// 获取文件详情，离线模式下文件的修改时间或大小变化后缓存自动失效
+#[command]
+pub async fn get_file_details(path: String) -> Result<FileDetails, ApiError> {
+    let online = read_online();
+    let key = if online {
+        format!("{}{}", OnlineFilesRepo::get_server_url(), path)
     } else {
-        OfflineFilesRepo::download_file(&path, &filename).await
+        format!("{}{}", read_base_dir(), path)
+    };
+
+    let cached = get_file_details_cache().lock().await.get(&key);
+    if let Some((details, cached_at)) = &cached {
+        let fresh = if online {
+            cached_at.elapsed() < ONLINE_FILE_DETAILS_TTL
+        } else {
+            is_unchanged_offline(&path, &details.file)
+        };
+        if fresh {
+            return Ok(details.clone());
+        }
     }
+
+    let details = if online {
+        match OnlineFilesRepo::get_file_details(&path).await {
+            Ok(details) => details,
+            // 服务器不可用时过期的缓存也比没有好
+            Err(e) if can_fall_back(&e) => return cached.map(|(details, _)| details).ok_or(e),
+            Err(e) => return Err(e),
+        }
+    } else {
+        OfflineFilesRepo::get_file_details(&path).await?
+    };
+    get_file_details_cache()
+        .lock()
+        .await
+        .put(key, (details.clone(), Instant::now()));
+    Ok(details)
+}
+
+fn is_unchanged_offline(path: &str, file: &FileInfo) -> bool {
+    let Some(metadata) = OfflineFilesRepo::resolve_read_path(path)
+        .ok()
+        .and_then(|real_path| real_path.metadata().ok())
+    else {
+        return false;
+    };
+    let last_modified = metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_secs());
+    metadata.len() == file.size && last_modified == Some(file.last_modified)
 }
@@ -0,0 +1,52 @@
+use std::time::Duration;
+
+use tauri::{command, AppHandle, Emitter};
+use tokio::time::sleep;
+
+use crate::commands::config::is_online;
+use crate::models::error::ApiError;
+use crate::models::file_index::IndexProgress;
+use crate::models::files::{FileInfo, SearchQuery};
+use crate::repos::offline::file_indexer::FileIndexer;
+
+// 后台增量更新索引的间隔
+const INDEX_UPDATE_INTERVAL: Duration = Duration::from_secs(10 * 60);
+
+/// 在离线索引中搜索，条件与`search_files`相同但不会遍历磁盘
+#[command]
+pub async fn search_index(query: SearchQuery) -> Result<Vec<FileInfo>, ApiError> {
+    if is_online() {
+        return Err(ApiError::new(400, "在线模式不支持索引搜索".to_string()));
+    }
+    FileIndexer::search(&query).await
+}
+
+/// 更新索引，进度通过`index-progress`事件推送；full为true时清空后重建
+#[command]
+pub async fn rebuild_index(full: Option<bool>, app: AppHandle) -> Result<IndexProgress, ApiError> {
+    if is_online() {
+        return Err(ApiError::new(400, "在线模式不支持索引搜索".to_string()));
+    }
+    FileIndexer::rebuild(full.unwrap_or(false), |progress| {
+        let _ = app.emit("index-progress", progress.clone());
+    })
+    .await
+}
+
+pub async fn start_index_auto_update(app: AppHandle) {
+    loop {
+        if !is_online() {
+            let result = FileIndexer::rebuild(false, |progress| {
+                let _ = app.emit("index-progress", progress.clone());
+            })
+            .await;
+            match result {
+                Ok(_) => {}
+                // 手动触发的更新正在进行
+                Err(e) if e.status_code == 409 => {}
+                Err(e) => eprintln!("更新文件索引失败: {}", e.message),
+            }
+        }
+        sleep(INDEX_UPDATE_INTERVAL).await;
+    }
+}
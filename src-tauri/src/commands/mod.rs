@@ -1,6 +1,14 @@
+pub mod auth;
 pub mod config;
+pub mod downloads;
+pub mod duplicates;
 pub mod favorites;
 pub mod files;
+pub mod index;
 pub mod os;
+pub mod preview;
+pub mod profiles;
+pub mod tags;
 pub mod thumbnail;
 pub mod transcode;
+pub mod trash;
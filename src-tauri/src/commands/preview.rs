@@ -0,0 +1,80 @@
+use encoding_rs::Encoding;
+use tauri::command;
+
+use crate::commands::config::read_online;
+use crate::models::error::ApiError;
+use crate::models::preview::{FileChunk, TextPreview};
+use crate::repos::files_repo::FilesRepo;
+use crate::repos::offline::offline_files_repo::OfflineFilesRepo;
+use crate::repos::online::online_files_repo::OnlineFilesRepo;
+use crate::utils::text_decode;
+
+// 单次按范围读取的上限和默认长度
+const READ_RANGE_MAX_LEN: u64 = 1024 * 1024;
+const READ_RANGE_DEFAULT_LEN: u64 = 64 * 1024;
+// 文本预览的上限和默认长度，超出部分需要前端改用read_file_range分段读取
+const PREVIEW_MAX_LEN: u64 = 1024 * 1024;
+const PREVIEW_DEFAULT_LEN: u64 = 256 * 1024;
+
+async fn read_range(path: &str, offset: u64, length: u64) -> Result<FileChunk, ApiError> {
+    if read_online() {
+        OnlineFilesRepo::read_file_range(path, offset, length).await
+    } else {
+        OfflineFilesRepo::read_file_range(path, offset, length).await
+    }
+}
+
+/// 从offset开始读取至多length字节，length超过上限时会被截断
+#[command]
+pub async fn read_file_range(
+    path: String,
+    offset: u64,
+    length: Option<u64>,
+) -> Result<FileChunk, ApiError> {
+    let length = length
+        .unwrap_or(READ_RANGE_DEFAULT_LEN)
+        .clamp(1, READ_RANGE_MAX_LEN);
+    read_range(&path, offset, length).await
+}
+
+/// 读取文件开头部分并解码为文本，encoding为空时自动探测（支持GBK/GB18030）
+#[command]
+pub async fn preview_text(
+    path: String,
+    max_bytes: Option<u64>,
+    encoding: Option<String>,
+) -> Result<TextPreview, ApiError> {
+    let forced = match encoding.as_deref() {
+        Some(label) => Some(
+            Encoding::for_label(label.as_bytes())
+                .ok_or_else(|| ApiError::new(400, format!("不支持的编码: {}", label)))?,
+        ),
+        None => None,
+    };
+
+    let length = max_bytes
+        .unwrap_or(PREVIEW_DEFAULT_LEN)
+        .clamp(1, PREVIEW_MAX_LEN);
+    let chunk = read_range(&path, 0, length).await?;
+    let truncated = !chunk.eof;
+
+    // 指定了编码时按用户的意思解码，不再判断是否为二进制
+    if chunk.is_binary && forced.is_none() {
+        return Ok(TextPreview {
+            content: String::new(),
+            encoding: String::new(),
+            is_binary: true,
+            truncated,
+            total_size: chunk.total_size,
+        });
+    }
+
+    let (content, encoding) = text_decode::decode(&chunk.data, truncated, forced);
+    Ok(TextPreview {
+        content,
+        encoding: encoding.name().to_string(),
+        is_binary: false,
+        truncated,
+        total_size: chunk.total_size,
+    })
+}
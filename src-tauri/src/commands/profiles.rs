@@ -0,0 +1,198 @@
+use tauri::{command, AppHandle, Emitter};
+
+use crate::commands::config::{reset_connection_state, update_app_config, with_config, AppConfig};
+use crate::models::error::ApiError;
+use crate::models::profile::{
+    ProfileChangedPayload, ProfileCredentials, ProfileCredentialsRequest, ProfileKind,
+    ProfileRequest, ServerProfile,
+};
+use crate::services::download_queue::DownloadQueue;
+use crate::services::private_store::PrivateStore;
+
+#[command]
+pub fn list_profiles() -> Vec<ServerProfile> {
+    with_config(|config| config.profiles.clone())
+}
+
+#[command]
+pub fn create_profile(request: ProfileRequest, app: AppHandle) -> Result<ServerProfile, ApiError> {
+    let (profile, password) = build_profile(uuid::Uuid::new_v4().to_string(), request, false)?;
+    update_app_config(&app, |config| {
+        config.profiles.push(profile.clone());
+        Ok(())
+    })?;
+    password.apply(&profile.id)?;
+    Ok(profile)
+}
+
+/// 修改的是当前激活的配置时，新的连接立即生效
+#[command]
+pub async fn update_profile(
+    id: String,
+    request: ProfileRequest,
+    app: AppHandle,
+) -> Result<ServerProfile, ApiError> {
+    let had_password = with_config(|config| {
+        config
+            .profiles
+            .iter()
+            .find(|p| p.id == id)
+            .map(|p| p.credentials.as_ref().is_some_and(|c| c.has_password))
+    })
+    .ok_or_else(not_found)?;
+    let (profile, password) = build_profile(id.clone(), request, had_password)?;
+    let config = update_app_config(&app, |config| {
+        let existing = find_profile(config, &id)?;
+        *existing = profile.clone();
+        Ok(())
+    })?;
+    password.apply(&id)?;
+    if config.active_profile_id.as_deref() == Some(id.as_str()) {
+        activate_profile(id, app).await?;
+    }
+    Ok(profile)
+}
+
+/// 删除激活的配置时只取消激活，当前连接保持不变
+#[command]
+pub fn delete_profile(id: String, app: AppHandle) -> Result<(), ApiError> {
+    update_app_config(&app, |config| {
+        let index = config
+            .profiles
+            .iter()
+            .position(|p| p.id == id)
+            .ok_or_else(not_found)?;
+        config.profiles.remove(index);
+        if config.active_profile_id.as_deref() == Some(id.as_str()) {
+            config.active_profile_id = None;
+        }
+        Ok(())
+    })?;
+    PrivateStore::set_profile_password(&id, None)
+}
+
+/// 切换到指定配置，清空与连接相关的缓存后推送`profile-changed`事件
+#[command]
+pub async fn activate_profile(id: String, app: AppHandle) -> Result<ServerProfile, ApiError> {
+    let previous_id = with_config(|config| config.active_profile_id.clone());
+    let mut profile = None;
+    update_app_config(&app, |config| {
+        let found = find_profile(config, &id)?.clone();
+        match found.kind {
+            ProfileKind::Server => {
+                config.online = true;
+                config.server_url = found.location.clone();
+            }
+            ProfileKind::Local => {
+                config.online = false;
+                config.base_dir = found.location.clone();
+            }
+        }
+        config.active_profile_id = Some(found.id.clone());
+        profile = Some(found);
+        Ok(())
+    })?;
+    let profile = profile.ok_or_else(not_found)?;
+
+    reset_connection_state(&app).await?;
+    DownloadQueue::schedule();
+
+    let _ = app.emit(
+        "profile-changed",
+        ProfileChangedPayload {
+            profile: profile.clone(),
+            previous_id,
+        },
+    );
+    Ok(profile)
+}
+
+/// 配置保存成功后才对密码做的修改，保存失败时密码不会和配置对不上
+enum PasswordChange {
+    Keep,
+    Clear,
+    Set(String),
+}
+
+impl PasswordChange {
+    fn apply(self, id: &str) -> Result<(), ApiError> {
+        match self {
+            PasswordChange::Keep => Ok(()),
+            PasswordChange::Clear => PrivateStore::set_profile_password(id, None),
+            PasswordChange::Set(password) => {
+                PrivateStore::set_profile_password(id, Some(&password))
+            }
+        }
+    }
+}
+
+/// 校验请求，返回新的配置和需要对密码做的修改，had_password为修改前是否保存了密码
+fn build_profile(
+    id: String,
+    request: ProfileRequest,
+    had_password: bool,
+) -> Result<(ServerProfile, PasswordChange), ApiError> {
+    let name = request.name.trim().to_string();
+    if name.is_empty() {
+        return Err(ApiError::new(400, "配置名称不能为空".to_string()));
+    }
+    let location = match request.kind {
+        ProfileKind::Server => request.location.trim().trim_end_matches('/').to_string(),
+        ProfileKind::Local => request.location.trim().to_string(),
+    };
+    if location.is_empty() {
+        return Err(ApiError::new(400, "服务器地址或目录不能为空".to_string()));
+    }
+    let credentials = match request.kind {
+        ProfileKind::Server => request.credentials,
+        ProfileKind::Local => None,
+    };
+    let (credentials, password) = plan_credentials(credentials, had_password);
+    let profile = ServerProfile {
+        id,
+        name,
+        kind: request.kind,
+        location,
+        credentials,
+    };
+    Ok((profile, password))
+}
+
+fn plan_credentials(
+    request: Option<ProfileCredentialsRequest>,
+    had_password: bool,
+) -> (Option<ProfileCredentials>, PasswordChange) {
+    let Some(request) = request else {
+        let password = if had_password {
+            PasswordChange::Clear
+        } else {
+            PasswordChange::Keep
+        };
+        return (None, password);
+    };
+    let (has_password, password) = match request.password {
+        None => (had_password, PasswordChange::Keep),
+        Some(password) if password.is_empty() => (false, PasswordChange::Clear),
+        Some(password) => (true, PasswordChange::Set(password)),
+    };
+    let credentials = ProfileCredentials {
+        username: request.username,
+        has_password,
+    };
+    (Some(credentials), password)
+}
+
+fn find_profile<'a>(
+    config: &'a mut AppConfig,
+    id: &str,
+) -> Result<&'a mut ServerProfile, ApiError> {
+    config
+        .profiles
+        .iter_mut()
+        .find(|p| p.id == id)
+        .ok_or_else(not_found)
+}
+
+fn not_found() -> ApiError {
+    ApiError::new(404, "连接配置不存在".to_string())
+}
@@ -0,0 +1,54 @@
+use tauri::command;
+
+use crate::commands::config::is_online;
+use crate::commands::files::get_file_details;
+use crate::models::error::ApiError;
+use crate::models::files::FileInfo;
+use crate::models::tag::{TagDto, TagFilesRequest};
+use crate::repos::offline::offline_files_repo::OfflineFilesRepo;
+use crate::repos::offline::tag_store::TagStore;
+use crate::repos::offline::OfflineRepo;
+use crate::utils::tag_expr::TagExpr;
+
+#[command]
+pub async fn list_tags() -> Result<Vec<TagDto>, ApiError> {
+    TagStore::list().await
+}
+
+#[command]
+pub async fn tag_files(request: TagFilesRequest) -> Result<(), ApiError> {
+    TagStore::tag(&request.paths, &request.tags).await
+}
+
+/// 返回实际移除的标签数
+#[command]
+pub async fn untag_files(request: TagFilesRequest) -> Result<u64, ApiError> {
+    TagStore::untag(&request.paths, &request.tags).await
+}
+
+/// 按`4K AND (to-review OR NOT done)`形式的表达式查找文件，已不存在的文件会被跳过
+#[command]
+pub async fn find_by_tags(expression: String) -> Result<Vec<FileInfo>, ApiError> {
+    let expr = TagExpr::parse(&expression)?;
+    let mut files = Vec::new();
+    for (path, tags) in TagStore::query(&expr).await? {
+        if let Some(mut file) = load_file_info(&path).await {
+            file.tags = tags;
+            files.push(file);
+        }
+    }
+    Ok(files)
+}
+
+async fn load_file_info(path: &str) -> Option<FileInfo> {
+    if is_online() {
+        return get_file_details(path.to_string())
+            .await
+            .ok()
+            .map(|d| d.file);
+    }
+    let resolver = OfflineFilesRepo::path_resolver().ok()?;
+    let real_path = resolver.resolve(path).ok()?;
+    let metadata = real_path.metadata().ok()?;
+    OfflineFilesRepo::build_file_info(&real_path, &metadata, &resolver).ok()
+}
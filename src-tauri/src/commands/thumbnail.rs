@@ -4,15 +4,17 @@ use std::sync::OnceLock;
 use tauri::{command, AppHandle, Emitter};
 use tokio::sync::{RwLock, Semaphore};
 
-use crate::commands::config::is_online;
+use crate::commands::config::{read_online, with_config};
 use crate::models::error::ApiError;
 use crate::repos::offline::offline_thumbnails_repo::OfflineThumbnailsRepo;
 use crate::repos::online::online_thumbnails_repo::OnlineThumbnailsRepo;
 use crate::repos::Repo;
 use crate::utils::lru_cache::LruCache;
 
-// 全局信号量，限制最多5个并发缩略图请求
+// 全局信号量，限制并发缩略图请求数，许可数随配置调整
 static THUMBNAIL_SEMAPHORE: OnceLock<Semaphore> = OnceLock::new();
+// 配置的并发数，缩小时已被占用的许可要等释放后才会真正减少
+static MAX_CONCURRENT: AtomicUsize = AtomicUsize::new(0);
 // 当前等待请求数量统计
 static WAITING_COUNT: AtomicUsize = AtomicUsize::new(0);
 // 当前处理中请求数量统计
@@ -21,7 +23,7 @@ static PROCESSING_COUNT: AtomicUsize = AtomicUsize::new(0);
 // 缩略图缓存类型别名
 type ThumbnailCache = LruCache<String, Vec<u8>>;
 
-// 全局缓存实例，容量设置为200个缩略图
+// 全局缓存实例，条目数和字节数上限来自配置
 static THUMBNAIL_CACHE: OnceLock<RwLock<ThumbnailCache>> = OnceLock::new();
 
 #[derive(Serialize, Deserialize)]
@@ -33,14 +35,75 @@ pub struct ThumbnailStatus {
     pub cache_size: usize,
     pub cache_max_size: usize,
     pub cache_memory_usage: usize,
+    pub cache_max_bytes: usize,
+}
+
+// 配置值的有效范围，超出时按边界处理
+const MAX_CONCURRENT_RANGE: (u32, u32) = (1, 32);
+const CACHE_ENTRIES_RANGE: (u32, u32) = (1, 10_000);
+const CACHE_BYTES_RANGE: (u64, u64) = (1024 * 1024, 1024 * 1024 * 1024);
+
+// 配置中的并发数、缓存条目数和字节数，字节数在32位平台上不超过usize::MAX
+fn thumbnail_limits() -> (usize, usize, usize) {
+    with_config(|config| {
+        let max_concurrent = config
+            .thumbnail_max_concurrent
+            .clamp(MAX_CONCURRENT_RANGE.0, MAX_CONCURRENT_RANGE.1);
+        let max_entries = config
+            .thumbnail_cache_entries
+            .clamp(CACHE_ENTRIES_RANGE.0, CACHE_ENTRIES_RANGE.1);
+        let max_bytes = config
+            .thumbnail_cache_bytes
+            .clamp(CACHE_BYTES_RANGE.0, CACHE_BYTES_RANGE.1);
+        (
+            max_concurrent as usize,
+            max_entries as usize,
+            usize::try_from(max_bytes).unwrap_or(usize::MAX),
+        )
+    })
 }
 
 fn get_thumbnail_semaphore() -> &'static Semaphore {
-    THUMBNAIL_SEMAPHORE.get_or_init(|| Semaphore::new(5))
+    THUMBNAIL_SEMAPHORE.get_or_init(|| {
+        let (max_concurrent, _, _) = thumbnail_limits();
+        MAX_CONCURRENT.store(max_concurrent, Ordering::Relaxed);
+        Semaphore::new(max_concurrent)
+    })
 }
 
 fn get_thumbnail_cache() -> &'static RwLock<ThumbnailCache> {
-    THUMBNAIL_CACHE.get_or_init(|| RwLock::new(LruCache::new(200)))
+    THUMBNAIL_CACHE.get_or_init(|| {
+        let (_, max_entries, _) = thumbnail_limits();
+        RwLock::new(LruCache::new(max_entries))
+    })
+}
+
+/// 配置修改后调用，不需要重启即可调整并发数和缓存大小
+pub async fn apply_thumbnail_limits(app: &AppHandle) {
+    let (max_concurrent, max_entries, max_bytes) = thumbnail_limits();
+
+    let semaphore = get_thumbnail_semaphore();
+    let previous = MAX_CONCURRENT.swap(max_concurrent, Ordering::Relaxed);
+    if max_concurrent > previous {
+        semaphore.add_permits(max_concurrent - previous);
+    } else if max_concurrent < previous {
+        // 等正在处理的请求释放许可后再收回多出的部分
+        tauri::async_runtime::spawn(async move {
+            if let Ok(permits) = semaphore
+                .acquire_many((previous - max_concurrent) as u32)
+                .await
+            {
+                permits.forget();
+            }
+        });
+    }
+
+    let mut cache = get_thumbnail_cache().write().await;
+    cache.set_max_size(max_entries);
+    cache.shrink_to_bytes(max_bytes);
+    drop(cache);
+
+    emit_thumbnail_status_update(app).await;
 }
 
 // 发送缩略图状态更新事件
@@ -63,13 +126,14 @@ async fn get_thumbnail_status_internal() -> Result<ThumbnailStatus, ApiError> {
     drop(cache); // 释放读锁
 
     Ok(ThumbnailStatus {
-        max_concurrent: 5,
+        max_concurrent: MAX_CONCURRENT.load(Ordering::Relaxed),
         current_waiting: waiting,
         current_processing: processing,
         available_slots: available,
         cache_size,
         cache_max_size,
         cache_memory_usage,
+        cache_max_bytes: thumbnail_limits().2,
     })
 }
 
@@ -93,7 +157,7 @@ pub async fn get_thumbnail(path: String, app: AppHandle) -> Result<Vec<u8>, ApiE
 
     emit_thumbnail_status_update(&app).await;
 
-    // 获取信号量许可，如果当前已有5个请求在处理，则等待
+    // 获取信号量许可，并发数已满时等待
     let _permit = get_thumbnail_semaphore()
         .acquire()
         .await
@@ -106,7 +170,7 @@ pub async fn get_thumbnail(path: String, app: AppHandle) -> Result<Vec<u8>, ApiE
     emit_thumbnail_status_update(&app).await;
 
     // 执行实际的缩略图获取操作
-    let result = if is_online() {
+    let result = if read_online() {
         OnlineThumbnailsRepo::get(path.clone()).await
     } else {
         OfflineThumbnailsRepo::get(path.clone()).await
@@ -123,6 +187,7 @@ pub async fn get_thumbnail(path: String, app: AppHandle) -> Result<Vec<u8>, ApiE
             {
                 let mut cache = get_thumbnail_cache().write().await;
                 cache.put(path.clone(), data.clone());
+                cache.shrink_to_bytes(thumbnail_limits().2);
             }
             // 缓存更新后再次发送状态更新事件
             emit_thumbnail_status_update(&app).await;
@@ -0,0 +1,111 @@
+use std::collections::HashSet;
+use std::time::Duration;
+
+use tauri::command;
+use tokio::time::sleep;
+
+use crate::commands::config::{is_online, with_config};
+use crate::models::error::ApiError;
+use crate::models::files::{ConflictPolicy, DeleteSummary, TransferResult};
+use crate::models::trash::TrashItemDto;
+use crate::repos::offline::offline_trash_repo::OfflineTrashRepo;
+use crate::repos::offline::tag_store::TagStore;
+use crate::repos::online::online_trash_repo::OnlineTrashRepo;
+use crate::repos::trash_repo::TrashRepo;
+use crate::repos::Repo;
+
+// 回收站过期清理的检查间隔
+const TRASH_PURGE_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+#[command]
+pub async fn list_trash() -> Result<Vec<TrashItemDto>, ApiError> {
+    if is_online() {
+        OnlineTrashRepo::get_all().await
+    } else {
+        OfflineTrashRepo::get_all().await
+    }
+}
+
+/// 逐个还原，这样才能知道每个条目还原到了哪里，把暂存的标签移过去
+#[command]
+pub async fn restore_from_trash(
+    ids: Vec<i64>,
+    conflict: Option<ConflictPolicy>,
+) -> Result<TransferResult, ApiError> {
+    let conflict = conflict.unwrap_or_default();
+    let mut result = TransferResult::default();
+    for id in ids {
+        let restored = if is_online() {
+            OnlineTrashRepo::restore(&[id], conflict).await?
+        } else {
+            OfflineTrashRepo::restore(&[id], conflict).await?
+        };
+        if let [path] = restored.completed.as_slice() {
+            TagStore::relocate(&TagStore::trash_path(id), path).await;
+        }
+        result.completed.extend(restored.completed);
+        result.skipped.extend(restored.skipped);
+        result.failed.extend(restored.failed);
+    }
+    Ok(result)
+}
+
+#[command]
+pub async fn empty_trash() -> Result<DeleteSummary, ApiError> {
+    let before = trash_ids().await?;
+    let summary = if is_online() {
+        OnlineTrashRepo::empty().await?
+    } else {
+        OfflineTrashRepo::empty().await?
+    };
+    forget_purged_tags(before).await;
+    Ok(summary)
+}
+
+async fn trash_ids() -> Result<HashSet<i64>, ApiError> {
+    Ok(list_trash()
+        .await?
+        .into_iter()
+        .map(|item| item.id)
+        .collect())
+}
+
+/// 删除已经从回收站中永久删除的条目暂存的标签，删除失败而留下的条目保留标签
+async fn forget_purged_tags(before: HashSet<i64>) {
+    let remaining = match trash_ids().await {
+        Ok(ids) => ids,
+        Err(e) => {
+            eprintln!("获取回收站失败，未清理标签: {}", e.message);
+            return;
+        }
+    };
+    for id in before.difference(&remaining) {
+        TagStore::forget(&TagStore::trash_path(*id)).await;
+    }
+}
+
+/// 定期永久删除离线回收站中超过保留天数的条目，保留天数为0时不清理
+pub async fn start_trash_auto_purge() {
+    loop {
+        let retention_days = with_config(|config| config.trash_retention_days);
+        // 过期清理只在离线回收站进行，清理前记下当前的条目以便删除它们的标签
+        let before = if retention_days > 0 && !is_online() {
+            trash_ids().await.ok()
+        } else {
+            None
+        };
+        if retention_days > 0 {
+            match OfflineTrashRepo::purge_expired(retention_days).await {
+                Ok(summary) if summary.removed_files > 0 => {
+                    println!("已清理回收站中的过期文件 {} 个", summary.removed_files)
+                }
+                Ok(_) => {}
+                Err(e) => eprintln!("清理回收站失败: {}", e.message),
+            }
+        }
+        if let Some(before) = before {
+            forget_purged_tags(before).await;
+        }
+        sleep(TRASH_PURGE_INTERVAL).await;
+    }
+}
@@ -1,17 +1,37 @@
 use tauri::{AppHandle, Emitter};
 
-use crate::commands::config::init_database;
+use crate::commands::config::{init_database, load_app_config};
+use crate::commands::index::start_index_auto_update;
+use crate::commands::trash::start_trash_auto_purge;
 use crate::commands::{
-    config::{get_app_config, set_app_config},
+    auth::{get_auth_status, login, logout},
+    config::{check_connectivity, get_app_config, set_app_config, validate_app_config},
+    downloads::{
+        cancel_download, enqueue_download, list_downloads, pause_download, resume_download,
+    },
+    duplicates::{cancel_find_duplicates, delete_duplicates, find_duplicates},
     favorites::{
         add_file_to_favorite, create_favorite, delete_favorite, delete_favorite_file,
-        get_all_favorite_files, get_favorites, update_favorite,
+        get_all_favorite_files, get_favorites, get_favorites_for_path, update_favorite,
+    },
+    files::{
+        cancel_folder_download, cancel_search, cancel_upload, copy_files, create_directory,
+        delete_directory, delete_file, download_file, download_folder, get_file_details, get_files,
+        move_files, rename_file, search_files, unwatch_directory, upload_files, watch_directory,
     },
-    files::{delete_file, download_file, get_files},
+    index::{rebuild_index, search_index},
     os::get_platform,
+    preview::{preview_text, read_file_range},
+    profiles::{activate_profile, create_profile, delete_profile, list_profiles, update_profile},
+    tags::{find_by_tags, list_tags, tag_files, untag_files},
     thumbnail::{clear_thumbnail_cache, get_thumbnail, get_thumbnail_status},
     transcode::{start_transcode, stop_transcode},
+    trash::{empty_trash, list_trash, restore_from_trash},
 };
+use crate::services::auth::AuthManager;
+use crate::services::connectivity::ConnectivityMonitor;
+use crate::services::download_queue::DownloadQueue;
+use crate::services::private_store::PrivateStore;
 
 mod commands;
 mod models;
@@ -24,14 +44,29 @@ pub fn run() {
     tauri::Builder::default()
         .plugin(tauri_plugin_store::Builder::new().build())
         .setup(|app| {
+            PrivateStore::init(app.handle());
+            if let Err(e) = load_app_config(app.handle()) {
+                eprintln!("读取配置失败，使用默认配置: {}", e.message);
+            }
+            if let Err(e) = AuthManager::init(app.handle()) {
+                eprintln!("读取登录信息失败: {}", e.message);
+            }
             let app_handle = app.handle().clone();
             tauri::async_runtime::spawn(async move {
                 start_udp_listener(&app_handle).await;
             });
+            tauri::async_runtime::spawn(ConnectivityMonitor::start(app.handle().clone()));
             let app_handle = app.handle().clone();
             tauri::async_runtime::spawn(async move {
                 match init_database(&app_handle).await {
-                    Ok(_) => println!("sqlite数据库初始化成功"),
+                    Ok(_) => {
+                        println!("sqlite数据库初始化成功");
+                        if let Err(e) = DownloadQueue::start(&app_handle).await {
+                            eprintln!("启动下载队列失败: {}", e.message);
+                        }
+                        tauri::async_runtime::spawn(start_index_auto_update(app_handle.clone()));
+                        start_trash_auto_purge().await;
+                    }
                     Err(e) => eprintln!("sqlite数据库初始化失败: {}", e.message),
                 };
             });
@@ -41,14 +76,55 @@ pub fn run() {
         .invoke_handler(tauri::generate_handler![
             get_app_config,
             set_app_config,
+            validate_app_config,
+            check_connectivity,
+            login,
+            logout,
+            get_auth_status,
+            list_profiles,
+            create_profile,
+            update_profile,
+            delete_profile,
+            activate_profile,
             get_platform,
             get_files,
             delete_file,
+            create_directory,
+            delete_directory,
             download_file,
+            download_folder,
+            cancel_folder_download,
+            enqueue_download,
+            list_downloads,
+            pause_download,
+            resume_download,
+            cancel_download,
+            search_files,
+            cancel_search,
+            search_index,
+            rebuild_index,
+            rename_file,
+            move_files,
+            copy_files,
+            upload_files,
+            cancel_upload,
+            watch_directory,
+            unwatch_directory,
+            get_file_details,
+            find_duplicates,
+            cancel_find_duplicates,
+            delete_duplicates,
+            read_file_range,
+            preview_text,
+            list_tags,
+            tag_files,
+            untag_files,
+            find_by_tags,
             get_thumbnail,
             get_thumbnail_status,
             clear_thumbnail_cache,
             get_favorites,
+            get_favorites_for_path,
             create_favorite,
             delete_favorite,
             update_favorite,
@@ -56,7 +132,10 @@ pub fn run() {
             get_all_favorite_files,
             delete_favorite_file,
             start_transcode,
-            stop_transcode
+            stop_transcode,
+            list_trash,
+            restore_from_trash,
+            empty_trash
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
@@ -95,7 +174,6 @@ async fn start_udp_listener(app: &AppHandle) {
         }
         Err(e) => {
             eprintln!("Failed to bind UDP socket on {}: {}", listen_address, e);
-            return;
         }
     }
 }
@@ -0,0 +1,56 @@
+use serde::Serialize;
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+pub enum CheckLevel {
+    Ok,
+    /// 不影响使用当前模式，但相关功能可能不可用
+    Warning,
+    /// 当前模式下无法正常工作，保存配置时会被拒绝
+    Error,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FieldCheck {
+    /// 对应AppConfig中的字段名，外部工具为工具名
+    pub field: String,
+    pub level: CheckLevel,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigCheckResult {
+    /// 没有Error级别的检查项
+    pub valid: bool,
+    pub checks: Vec<FieldCheck>,
+}
+
+impl FieldCheck {
+    pub fn new(field: &str, level: CheckLevel, message: impl Into<String>) -> Self {
+        Self {
+            field: field.to_string(),
+            level,
+            message: message.into(),
+        }
+    }
+}
+
+impl ConfigCheckResult {
+    pub fn new(checks: Vec<FieldCheck>) -> Self {
+        Self {
+            valid: checks.iter().all(|c| c.level != CheckLevel::Error),
+            checks,
+        }
+    }
+
+    /// 把所有错误合并成一条提示
+    pub fn error_message(&self) -> String {
+        self.checks
+            .iter()
+            .filter(|c| c.level == CheckLevel::Error)
+            .map(|c| format!("{}: {}", c.field, c.message))
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+}
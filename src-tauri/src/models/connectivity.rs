@@ -0,0 +1,12 @@
+use serde::Serialize;
+
+/// 连接状态，变化时通过`connectivity-changed`事件推送
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectivityStatus {
+    pub server_url: String,
+    /// 离线模式下不检查服务器，始终为true
+    pub reachable: bool,
+    /// 只读操作正在使用离线镜像
+    pub using_mirror: bool,
+}
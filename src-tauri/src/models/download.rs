@@ -0,0 +1,104 @@
+use std::fmt::Display;
+
+use serde::{Deserialize, Serialize};
+use sqlx::types::chrono::{DateTime, Utc};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum DownloadStatus {
+    Queued,
+    Downloading,
+    Paused,
+    Completed,
+    Failed,
+    Cancelled,
+}
+
+impl DownloadStatus {
+    /// 已结束的任务不会再被调度
+    pub fn is_finished(&self) -> bool {
+        matches!(
+            self,
+            DownloadStatus::Completed | DownloadStatus::Failed | DownloadStatus::Cancelled
+        )
+    }
+}
+
+impl Display for DownloadStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let status = match self {
+            DownloadStatus::Queued => "Queued",
+            DownloadStatus::Downloading => "Downloading",
+            DownloadStatus::Paused => "Paused",
+            DownloadStatus::Completed => "Completed",
+            DownloadStatus::Failed => "Failed",
+            DownloadStatus::Cancelled => "Cancelled",
+        };
+        write!(f, "{}", status)
+    }
+}
+
+impl From<&str> for DownloadStatus {
+    fn from(s: &str) -> Self {
+        match s {
+            "Downloading" => DownloadStatus::Downloading,
+            "Paused" => DownloadStatus::Paused,
+            "Completed" => DownloadStatus::Completed,
+            "Failed" => DownloadStatus::Failed,
+            "Cancelled" => DownloadStatus::Cancelled,
+            _ => DownloadStatus::Queued,
+        }
+    }
+}
+
+/// 下载队列中的一项，同时作为`download-status`事件的负载
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadItemDto {
+    pub id: i64,
+    /// 所属的服务器地址或base_dir，与当前配置不同的项不会被调度
+    pub source: String,
+    pub path: String,
+    pub filename: String,
+    pub status: DownloadStatus,
+    pub downloaded_bytes: u64,
+    pub total_bytes: Option<u64>,
+    /// 只在下载中有值，字节每秒
+    pub speed: u64,
+    pub error: Option<String>,
+    pub created_at: i64,
+    pub updated_at: i64,
+}
+
+/// 数据库实体
+#[derive(Debug, Clone, sqlx::FromRow)]
+pub struct DownloadItem {
+    pub id: i64,
+    pub online: bool,
+    pub source: String,
+    pub path: String,
+    pub filename: String,
+    pub status: String,
+    pub downloaded_bytes: i64,
+    pub total_bytes: Option<i64>,
+    pub error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<DownloadItem> for DownloadItemDto {
+    fn from(item: DownloadItem) -> Self {
+        Self {
+            id: item.id,
+            source: item.source,
+            path: item.path,
+            filename: item.filename,
+            status: DownloadStatus::from(item.status.as_str()),
+            downloaded_bytes: item.downloaded_bytes.max(0) as u64,
+            total_bytes: item.total_bytes.map(|t| t.max(0) as u64),
+            speed: 0,
+            error: item.error,
+            created_at: item.created_at.timestamp_millis(),
+            updated_at: item.updated_at.timestamp_millis(),
+        }
+    }
+}
@@ -0,0 +1,42 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::files::FileInfo;
+
+/// 一组内容完全相同的文件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateGroup {
+    pub size: u64,
+    /// 内容的blake3哈希
+    pub hash: String,
+    pub files: Vec<FileInfo>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, PartialEq)]
+pub enum DuplicateStage {
+    /// 遍历目录并按大小分组
+    #[default]
+    Scanning,
+    /// 对大小相同的文件计算哈希
+    Hashing,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateProgress {
+    pub stage: DuplicateStage,
+    pub scanned_files: u64,
+    /// 需要计算哈希的文件数和字节数，扫描阶段结束后才确定
+    pub total_files: u64,
+    pub total_bytes: u64,
+    pub hashed_files: u64,
+    pub hashed_bytes: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DuplicateProgressPayload {
+    pub task_id: String,
+    #[serde(flatten)]
+    pub progress: DuplicateProgress,
+}
@@ -14,7 +14,7 @@ pub struct FavoriteDto {
     pub files: Vec<FavoriteFileDto>,
 }
 
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct FavoriteFileDto {
     pub id: i64,
@@ -96,12 +96,7 @@ impl From<FavoriteFile> for FavoriteFileDto {
             favorite_id: file.favorite_id,
             filename: file.filename,
             file_size: file.file_size,
-            file_type: match file.file_type.as_str() {
-                "Folder" => FileType::Folder,
-                "Image" => FileType::Image,
-                "Video" => FileType::Video,
-                _ => FileType::Other,
-            },
+            file_type: FileType::from(file.file_type.as_str()),
             file_path: file.file_path,
             last_modified: file.last_modified,
             is_directory: file.is_directory,
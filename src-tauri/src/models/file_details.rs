@@ -0,0 +1,83 @@
+use serde::{Deserialize, Serialize};
+
+use crate::models::files::FileInfo;
+
+/// 文件的详细信息，media和image只在对应类型的文件上存在
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileDetails {
+    #[serde(flatten)]
+    pub file: FileInfo,
+    /// 创建时间（秒），部分文件系统不支持时为空
+    pub created: Option<u64>,
+    /// unix下为`rwxr-xr-x`形式，其他平台只区分`r--`和`rw-`
+    pub permissions: String,
+    pub mime_type: String,
+    pub media: Option<MediaDetails>,
+    pub image: Option<ImageDetails>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MediaDetails {
+    pub format_name: Option<String>,
+    /// 时长（秒）
+    pub duration: Option<f64>,
+    /// 总码率，比特每秒
+    pub bit_rate: Option<u64>,
+    pub video_streams: Vec<VideoStream>,
+    pub audio_tracks: Vec<AudioTrack>,
+    pub subtitle_tracks: Vec<SubtitleTrack>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VideoStream {
+    pub index: u32,
+    pub codec: String,
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub frame_rate: Option<f64>,
+    pub bit_rate: Option<u64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AudioTrack {
+    pub index: u32,
+    pub codec: String,
+    pub channels: Option<u32>,
+    pub sample_rate: Option<u32>,
+    pub bit_rate: Option<u64>,
+    pub language: Option<String>,
+    pub title: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubtitleTrack {
+    pub index: u32,
+    pub codec: String,
+    pub language: Option<String>,
+    pub title: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ImageDetails {
+    pub width: Option<u32>,
+    pub height: Option<u32>,
+    pub camera_make: Option<String>,
+    pub camera_model: Option<String>,
+    /// EXIF中的原始拍摄时间，格式为`YYYY:MM:DD HH:MM:SS`
+    pub date_taken: Option<String>,
+    pub gps: Option<GpsCoordinates>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GpsCoordinates {
+    pub latitude: f64,
+    pub longitude: f64,
+    pub altitude: Option<f64>,
+}
@@ -0,0 +1,42 @@
+use serde::Serialize;
+
+use crate::models::files::{FileInfo, FileType};
+
+/// 建立索引的进度，同时作为`index-progress`事件的负载和完成后的统计
+#[derive(Debug, Clone, Default, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IndexProgress {
+    /// 已遍历的条目数
+    pub scanned_entries: u64,
+    /// 新增或因修改时间变化而更新的条目数
+    pub updated_entries: u64,
+    /// 磁盘上已不存在而被删除的条目数
+    pub removed_entries: u64,
+    pub finished: bool,
+}
+
+/// 数据库实体，只包含搜索结果需要的列
+#[derive(Debug, sqlx::FromRow)]
+pub struct IndexedFile {
+    pub path: String,
+    pub name: String,
+    pub is_directory: bool,
+    pub size: i64,
+    pub last_modified: i64,
+    pub file_type: String,
+}
+
+impl From<IndexedFile> for FileInfo {
+    fn from(file: IndexedFile) -> Self {
+        Self {
+            name: file.name,
+            size: file.size.max(0) as u64,
+            file_type: FileType::from(file.file_type.as_str()),
+            is_directory: file.is_directory,
+            last_modified: file.last_modified.max(0) as u64,
+            path: file.path,
+            tags: Vec::new(),
+            favorite_ids: Vec::new(),
+        }
+    }
+}
@@ -1,6 +1,11 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize, Deserialize)]
+use crate::{
+    models::error::ApiError,
+    utils::{file_sniff, natural_order::natural_cmp},
+};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct FileInfo {
     pub name: String,
@@ -10,44 +15,511 @@ pub struct FileInfo {
     pub is_directory: bool,
     pub last_modified: u64,
     pub path: String,
+    /// 本机记录的标签，只在get_files的结果中填充
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// 包含该文件的收藏夹id，只在get_files的结果中填充
+    #[serde(default)]
+    pub favorite_ids: Vec<i64>,
 }
 
-#[derive(Debug, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
 pub enum FileType {
     Folder,
     Image,
     Video,
+    Audio,
+    Text,
+    Document,
+    Archive,
     Other,
 }
 
-impl ToString for FileType {
-    fn to_string(&self) -> String {
-        match self {
-            FileType::Folder => "Folder".to_string(),
-            FileType::Image => "Image".to_string(),
-            FileType::Video => "Video".to_string(),
-            FileType::Other => "Other".to_string(),
+impl std::fmt::Display for FileType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            FileType::Folder => "Folder",
+            FileType::Image => "Image",
+            FileType::Video => "Video",
+            FileType::Audio => "Audio",
+            FileType::Text => "Text",
+            FileType::Document => "Document",
+            FileType::Archive => "Archive",
+            FileType::Other => "Other",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// 与Display互逆，数据库中保存的就是这个名字，无法识别的名字视为Other
+impl From<&str> for FileType {
+    fn from(name: &str) -> Self {
+        match name {
+            "Folder" => FileType::Folder,
+            "Image" => FileType::Image,
+            "Video" => FileType::Video,
+            "Audio" => FileType::Audio,
+            "Text" => FileType::Text,
+            "Document" => FileType::Document,
+            "Archive" => FileType::Archive,
+            _ => FileType::Other,
+        }
+    }
+}
+
+/// 目录列表的排序字段
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub enum SortKey {
+    #[default]
+    Name,
+    Size,
+    Modified,
+    Type,
+}
+
+impl std::fmt::Display for SortKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            SortKey::Name => "Name",
+            SortKey::Size => "Size",
+            SortKey::Modified => "Modified",
+            SortKey::Type => "Type",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub enum SortDirection {
+    #[default]
+    Asc,
+    Desc,
+}
+
+impl std::fmt::Display for SortDirection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            SortDirection::Asc => "Asc",
+            SortDirection::Desc => "Desc",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// 目录列表的排序、过滤和分页选项，目录始终排在文件之前
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ListOptions {
+    pub sort_by: SortKey,
+    pub direction: SortDirection,
+    /// 文件名中的数字按数值排序，如`ep2`排在`ep10`之前
+    pub natural_order: bool,
+    /// 为空时不限制类型
+    pub file_types: Vec<FileType>,
+    pub offset: usize,
+    /// 为空时返回offset之后的全部条目
+    pub limit: Option<usize>,
+}
+
+impl Default for ListOptions {
+    fn default() -> Self {
+        Self {
+            sort_by: SortKey::default(),
+            direction: SortDirection::default(),
+            natural_order: true,
+            file_types: Vec::new(),
+            offset: 0,
+            limit: None,
+        }
+    }
+}
+
+impl ListOptions {
+    /// 转换为追加在已有查询参数之后的查询字符串，以`&`开头
+    pub fn to_query_string(&self) -> String {
+        let mut query = format!(
+            "&sortBy={}&direction={}&naturalOrder={}&offset={}",
+            self.sort_by, self.direction, self.natural_order, self.offset
+        );
+        if !self.file_types.is_empty() {
+            let types: Vec<String> = self.file_types.iter().map(|t| t.to_string()).collect();
+            query.push_str(&format!("&fileTypes={}", types.join(",")));
+        }
+        if let Some(limit) = self.limit {
+            query.push_str(&format!("&limit={}", limit));
+        }
+        query
+    }
+
+    /// 在本地对完整的目录内容做过滤、排序和分页
+    pub fn apply(&self, mut files: Vec<FileInfo>) -> FileListing {
+        files
+            .retain(|file| self.file_types.is_empty() || self.file_types.contains(&file.file_type));
+        self.sort(&mut files);
+
+        let total = files.len();
+        let files = files
+            .into_iter()
+            .skip(self.offset)
+            .take(self.limit.unwrap_or(usize::MAX))
+            .collect();
+        FileListing { files, total }
+    }
+
+    fn sort(&self, files: &mut [FileInfo]) {
+        let compare_names = |a: &FileInfo, b: &FileInfo| {
+            if self.natural_order {
+                natural_cmp(&a.name, &b.name)
+            } else {
+                a.name.cmp(&b.name)
+            }
+        };
+
+        files.sort_by(|a, b| {
+            // 无论按什么字段排序，目录都排在文件前面
+            b.is_directory.cmp(&a.is_directory).then_with(|| {
+                let ordering = match self.sort_by {
+                    SortKey::Name => compare_names(a, b),
+                    SortKey::Size => a.size.cmp(&b.size).then_with(|| compare_names(a, b)),
+                    SortKey::Modified => a
+                        .last_modified
+                        .cmp(&b.last_modified)
+                        .then_with(|| compare_names(a, b)),
+                    SortKey::Type => a
+                        .file_type
+                        .cmp(&b.file_type)
+                        .then_with(|| compare_names(a, b)),
+                };
+                match self.direction {
+                    SortDirection::Asc => ordering,
+                    SortDirection::Desc => ordering.reverse(),
+                }
+            })
+        });
+    }
+}
+
+/// 分页后的目录列表，total为过滤后、分页前的条目总数
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileListing {
+    pub files: Vec<FileInfo>,
+    pub total: usize,
+}
+
+/// 服务端的目录列表响应，旧版本的服务端直接返回不分页的文件数组
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum FileListingResponse {
+    Paged(FileListing),
+    Legacy(Vec<FileInfo>),
+}
+
+/// 递归搜索的过滤条件，所有条件之间为“与”关系
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchQuery {
+    /// 搜索的起始目录，默认为根目录
+    #[serde(default = "default_search_root")]
+    pub root: String,
+    /// 文件名模式，含`*`或`?`时按通配符匹配，否则按子串匹配，均忽略大小写
+    pub pattern: Option<String>,
+    /// 为空时不限制类型
+    #[serde(default)]
+    pub file_types: Vec<FileType>,
+    pub min_size: Option<u64>,
+    pub max_size: Option<u64>,
+    /// 修改时间下限，秒级时间戳
+    pub modified_after: Option<u64>,
+    /// 修改时间上限，秒级时间戳
+    pub modified_before: Option<u64>,
+    /// 最多返回的结果数量
+    pub limit: Option<usize>,
+}
+
+/// 单次搜索最多返回的结果数量
+pub const SEARCH_MAX_RESULTS: usize = 5000;
+
+fn default_search_root() -> String {
+    "/".to_string()
+}
+
+impl SearchQuery {
+    /// 实际生效的结果数量上限
+    pub fn limit(&self) -> usize {
+        self.limit
+            .unwrap_or(SEARCH_MAX_RESULTS)
+            .min(SEARCH_MAX_RESULTS)
+    }
+
+    /// 除文件名以外的条件是否满足，文件名由各存储库自行匹配
+    pub fn matches_attributes(&self, file: &FileInfo) -> bool {
+        if !self.file_types.is_empty() && !self.file_types.contains(&file.file_type) {
+            return false;
+        }
+        if self.min_size.is_some_and(|min| file.size < min)
+            || self.max_size.is_some_and(|max| file.size > max)
+        {
+            return false;
         }
+        if self.modified_after.is_some_and(|t| file.last_modified < t)
+            || self.modified_before.is_some_and(|t| file.last_modified > t)
+        {
+            return false;
+        }
+        true
     }
 }
 
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchResultPayload {
+    pub search_id: String,
+    pub file: FileInfo,
+}
+
 pub trait ToFileType {
     fn to_file_type(&self) -> FileType;
 }
 
+impl FileType {
+    /// 只按扩展名判断类型，无法识别或有歧义（如`ts`）时返回None
+    pub fn from_extension(extension: &str) -> Option<FileType> {
+        match extension.to_lowercase().as_str() {
+            "png" | "jpg" | "jpeg" | "gif" | "bmp" | "svg" | "webp" | "avif" | "heic" | "heif"
+            | "tif" | "tiff" | "ico" => Some(FileType::Image),
+            "mp4" | "mkv" | "avi" | "mov" | "wmv" | "flv" | "3gp" | "webm" | "m4v" | "mpg"
+            | "mpeg" | "m2ts" | "mts" | "rmvb" => Some(FileType::Video),
+            "mp3" | "flac" | "wav" | "aac" | "m4a" | "ogg" | "opus" | "wma" | "ape" => {
+                Some(FileType::Audio)
+            }
+            "txt" | "md" | "log" | "json" | "xml" | "yaml" | "yml" | "toml" | "ini" | "csv"
+            | "srt" | "ass" | "vtt" | "lrc" => Some(FileType::Text),
+            "pdf" | "doc" | "docx" | "xls" | "xlsx" | "ppt" | "pptx" | "odt" | "ods" | "odp"
+            | "epub" | "rtf" => Some(FileType::Document),
+            "zip" | "rar" | "7z" | "tar" | "gz" | "tgz" | "bz2" | "xz" | "zst" => {
+                Some(FileType::Archive)
+            }
+            _ => None,
+        }
+    }
+
+    /// 无法读取文件内容时的兜底判断，`ts`按视频处理
+    pub fn from_name(name: &str) -> FileType {
+        let extension = std::path::Path::new(name)
+            .extension()
+            .and_then(|ext| ext.to_str());
+        match extension {
+            Some(ext) if ext.eq_ignore_ascii_case("ts") => FileType::Video,
+            Some(ext) => FileType::from_extension(ext).unwrap_or(FileType::Other),
+            None => FileType::Other,
+        }
+    }
+}
+
+/// 先按扩展名判断，扩展名缺失、无法识别或有歧义（如`ts`）时再读取文件头判断
 impl ToFileType for std::path::Path {
     fn to_file_type(&self) -> FileType {
         if self.is_dir() {
             return FileType::Folder;
         }
 
-        match self.extension().and_then(|ext| ext.to_str()) {
-            Some(ext) => match ext.to_lowercase().as_str() {
-                "png" | "jpg" | "jpeg" | "gif" | "bmp" | "svg" => FileType::Image,
-                "mp4" | "mkv" | "avi" | "mov" | "wmv" | "flv" | "3gp" => FileType::Video,
-                _ => FileType::Other,
-            },
-            None => FileType::Other,
+        let extension = self.extension().and_then(|ext| ext.to_str());
+        // ts既可能是MPEG-TS视频也可能是TypeScript源码，读不出文件头时按视频处理
+        extension
+            .and_then(FileType::from_extension)
+            .or_else(|| file_sniff::sniff_file_type(self))
+            .unwrap_or_else(|| {
+                let name = self
+                    .file_name()
+                    .and_then(|n| n.to_str())
+                    .unwrap_or_default();
+                FileType::from_name(name)
+            })
+    }
+}
+
+/// 删除目录的统计结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeleteSummary {
+    pub removed_files: u64,
+    pub removed_bytes: u64,
+    /// 未能删除的条目，其余条目照常处理
+    #[serde(default)]
+    pub failed: Vec<TransferFailure>,
+}
+
+/// 目标位置已存在同名文件时的处理策略
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub enum ConflictPolicy {
+    /// 删除已存在的文件后写入
+    Overwrite,
+    /// 保留已存在的文件，跳过当前条目
+    #[default]
+    Skip,
+    /// 自动改名为`name (1).ext`的形式
+    Rename,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenameRequest {
+    pub path: String,
+    pub new_name: String,
+    #[serde(default)]
+    pub conflict: ConflictPolicy,
+}
+
+/// 移动或复制请求，sources中的条目都会被放到target_dir下
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferRequest {
+    pub sources: Vec<String>,
+    pub target_dir: String,
+    #[serde(default)]
+    pub conflict: ConflictPolicy,
+}
+
+/// 上传请求，local_paths为本机的绝对路径，可以是文件或目录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UploadRequest {
+    pub local_paths: Vec<String>,
+    pub target_dir: String,
+    #[serde(default)]
+    pub conflict: ConflictPolicy,
+}
+
+/// 目录下载的保存方式
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq)]
+pub enum FolderDownloadMode {
+    /// 打包为一个zip文件
+    #[default]
+    Zip,
+    /// 不打包，按原有目录结构逐个保存文件
+    Folder,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FolderDownloadRequest {
+    pub path: String,
+    /// 保存的文件名或目录名（不含扩展名），为空时使用目录名
+    pub name: Option<String>,
+    #[serde(default)]
+    pub mode: FolderDownloadMode,
+}
+
+impl FolderDownloadRequest {
+    /// 保存的名称只能是单独的文件名，不能借助路径写到下载目录之外
+    pub fn target_name(&self) -> Result<String, ApiError> {
+        if let Some(name) = self.name.as_deref().map(str::trim) {
+            if !name.is_empty() {
+                if name == "." || name == ".." || name.contains(['/', '\\']) {
+                    return Err(ApiError::new(400, "无效的保存名称".to_string()));
+                }
+                return Ok(name.to_string());
+            }
         }
+        Ok(self
+            .path
+            .rsplit(['/', '\\'])
+            .find(|part| !part.is_empty() && *part != "." && *part != "..")
+            .unwrap_or("download")
+            .to_string())
     }
 }
+
+/// 向服务端申请上传会话，path为目标文件的完整路径
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UploadInitRequest {
+    pub path: String,
+    pub size: u64,
+    pub conflict: ConflictPolicy,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UploadSession {
+    pub upload_id: String,
+    /// 按冲突策略处理后最终写入的路径
+    pub path: String,
+    /// 为true时表示目标已存在且策略为跳过，无需上传
+    pub skipped: bool,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferResult {
+    /// 完成后的新路径
+    pub completed: Vec<String>,
+    /// 因冲突被跳过的源路径
+    pub skipped: Vec<String>,
+    pub failed: Vec<TransferFailure>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferFailure {
+    pub path: String,
+    pub message: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferProgress {
+    pub current_file: String,
+    pub copied_bytes: u64,
+    pub total_bytes: u64,
+    pub copied_files: u64,
+    pub total_files: u64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TransferProgressPayload {
+    pub task_id: String,
+    #[serde(flatten)]
+    pub progress: TransferProgress,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DownloadProgress {
+    pub path: String,
+    pub filename: String,
+    pub downloaded_bytes: u64,
+    /// 服务端没有返回Content-Length时为空
+    pub total_bytes: Option<u64>,
+    /// 最近一段时间的平均速度，字节每秒
+    pub speed: u64,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, PartialEq)]
+pub enum FileChangeKind {
+    Created,
+    Modified,
+    Removed,
+    Renamed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileChange {
+    pub kind: FileChangeKind,
+    pub path: String,
+    /// 重命名前的路径，仅Renamed时存在
+    pub old_path: Option<String>,
+    /// 变更后的文件信息，Removed时为空
+    pub file: Option<FileInfo>,
+}
+
+/// 监听目录的一批去抖后的变更
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FilesChangedPayload {
+    pub path: String,
+    pub changes: Vec<FileChange>,
+}
@@ -1,5 +1,16 @@
 pub mod api_response;
+pub mod auth;
+pub mod config_check;
+pub mod connectivity;
+pub mod download;
+pub mod duplicates;
 pub mod error;
 pub mod favorite;
+pub mod file_details;
+pub mod file_index;
 pub mod files;
+pub mod preview;
+pub mod profile;
+pub mod tag;
 pub mod transcode;
+pub mod trash;
@@ -0,0 +1,56 @@
+use base64::{engine::general_purpose::STANDARD, Engine};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::utils::text_decode;
+
+/// 按字节范围读取到的文件内容
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileChunk {
+    pub offset: u64,
+    /// 以base64字符串传给前端，JSON数字数组的体积约是原始数据的4倍
+    #[serde(
+        serialize_with = "serialize_base64",
+        deserialize_with = "deserialize_base64"
+    )]
+    pub data: Vec<u8>,
+    pub total_size: u64,
+    /// 内容看起来不是文本，前端应改用十六进制视图
+    pub is_binary: bool,
+    /// 已经读到文件末尾
+    pub eof: bool,
+}
+
+impl FileChunk {
+    pub fn new(offset: u64, data: Vec<u8>, total_size: u64) -> Self {
+        Self {
+            offset,
+            is_binary: text_decode::is_binary(&data),
+            eof: offset + data.len() as u64 >= total_size,
+            data,
+            total_size,
+        }
+    }
+}
+
+fn serialize_base64<S: Serializer>(data: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&STANDARD.encode(data))
+}
+
+fn deserialize_base64<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+    let encoded = String::deserialize(deserializer)?;
+    STANDARD.decode(encoded).map_err(serde::de::Error::custom)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextPreview {
+    /// 二进制内容时为空
+    pub content: String,
+    /// 实际使用的编码名，如`UTF-8`、`GBK`
+    pub encoding: String,
+    pub is_binary: bool,
+    /// 文件超出预览上限，只返回了开头部分
+    pub truncated: bool,
+    pub total_size: u64,
+}
@@ -0,0 +1,55 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+pub enum ProfileKind {
+    /// 连接远程服务器，location为服务器地址
+    Server,
+    /// 直接浏览本机目录，location为base_dir
+    Local,
+}
+
+/// 密码单独保存在私有文件中，配置和返回给前端的数据里只标记是否保存了密码
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileCredentials {
+    pub username: String,
+    #[serde(default)]
+    pub has_password: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileCredentialsRequest {
+    pub username: String,
+    /// 为空时沿用已保存的密码，空字符串表示删除密码
+    pub password: Option<String>,
+}
+
+/// 命名的连接配置，激活后写入AppConfig的online、server_url或base_dir
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerProfile {
+    pub id: String,
+    pub name: String,
+    pub kind: ProfileKind,
+    pub location: String,
+    /// 只对Server类型有意义
+    pub credentials: Option<ProfileCredentials>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileRequest {
+    pub name: String,
+    pub kind: ProfileKind,
+    pub location: String,
+    pub credentials: Option<ProfileCredentialsRequest>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProfileChangedPayload {
+    pub profile: ServerProfile,
+    /// 切换前激活的配置id，之前没有激活任何配置时为空
+    pub previous_id: Option<String>,
+}
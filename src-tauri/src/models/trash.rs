@@ -0,0 +1,53 @@
+use serde::{Deserialize, Serialize};
+use sqlx::types::chrono::{DateTime, Utc};
+
+use crate::models::files::ConflictPolicy;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrashItemDto {
+    pub id: i64,
+    pub name: String,
+    pub original_path: String,
+    pub is_directory: bool,
+    pub file_count: i64,
+    pub file_size: i64,
+    pub deleted_at: i64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RestoreTrashRequest {
+    pub ids: Vec<i64>,
+    #[serde(default)]
+    pub conflict: ConflictPolicy,
+}
+
+/// 数据库实体
+#[derive(Debug, sqlx::FromRow)]
+pub struct TrashItem {
+    pub id: i64,
+    pub base_dir: String,
+    pub original_path: String,
+    pub name: String,
+    /// 在回收站目录中的文件名
+    pub trash_name: String,
+    pub is_directory: bool,
+    pub file_count: i64,
+    pub file_size: i64,
+    pub deleted_at: DateTime<Utc>,
+}
+
+impl From<TrashItem> for TrashItemDto {
+    fn from(item: TrashItem) -> Self {
+        Self {
+            id: item.id,
+            name: item.name,
+            original_path: item.original_path,
+            is_directory: item.is_directory,
+            file_count: item.file_count,
+            file_size: item.file_size,
+            deleted_at: item.deleted_at.timestamp_millis(),
+        }
+    }
+}
@@ -1,7 +1,9 @@
+use std::collections::HashMap;
+
 use crate::{
     models::{
         error::ApiError,
-        favorite::{AddFileToFavoriteRequest, FavoriteFileDto},
+        favorite::{AddFileToFavoriteRequest, FavoriteDto, FavoriteFileDto},
     },
     repos::Repo,
 };
@@ -13,4 +15,10 @@ pub trait FavoritesRepo: Repo {
     ) -> Result<bool, ApiError>;
     async fn get_all_favorite_files() -> Result<Vec<FavoriteFileDto>, ApiError>;
     async fn delete_favorite_file(id: i64) -> Result<bool, ApiError>;
+    /// 包含该路径的收藏夹，files中只保留与该路径对应的条目
+    async fn get_favorites_for_path(path: &str) -> Result<Vec<FavoriteDto>, ApiError>;
+    /// 每个路径所在的收藏夹id，不在任何收藏夹中的路径不会出现在结果中
+    async fn get_favorite_ids_for_paths(
+        paths: &[String],
+    ) -> Result<HashMap<String, Vec<i64>>, ApiError>;
 }
@@ -1,9 +1,87 @@
+use std::path::PathBuf;
+
 use crate::{
-    models::{error::ApiError, files::FileInfo},
+    models::{
+        error::ApiError,
+        file_details::FileDetails,
+        files::{
+            DeleteSummary, DownloadProgress, FileInfo, FileListing, FolderDownloadRequest,
+            ListOptions, RenameRequest, SearchQuery, TransferProgress, TransferRequest,
+            TransferResult, UploadRequest,
+        },
+        preview::FileChunk,
+    },
     repos::Repo,
+    utils::cancellation::CancelToken,
 };
 
 pub trait FilesRepo: Repo {
-    async fn get_files(path: &str) -> Result<Vec<FileInfo>, ApiError>;
-    async fn download_file(path: &str, filename: &str) -> Result<(), ApiError>;
+    async fn get_files(path: &str, options: &ListOptions) -> Result<FileListing, ApiError>;
+    async fn download_file(
+        path: &str,
+        filename: &str,
+        on_progress: impl FnMut(&DownloadProgress) + Send,
+    ) -> Result<(), ApiError>;
+    /// 递归搜索文件，每找到一个结果就调用一次`on_found`，达到上限或被取消时提前返回；
+    /// 不能边搜索边推送结果的实现返回501
+    async fn search(
+        query: &SearchQuery,
+        token: &CancelToken,
+        on_found: impl FnMut(&FileInfo) + Send,
+    ) -> Result<Vec<FileInfo>, ApiError>;
+    async fn create_directory(path: &str) -> Result<FileInfo, ApiError>;
+    /// 删除目录，recursive为false时只能删除空目录
+    async fn delete_directory(path: &str, recursive: bool) -> Result<DeleteSummary, ApiError>;
+    /// 重命名文件或目录，返回新的路径
+    async fn rename(request: &RenameRequest) -> Result<String, ApiError>;
+    async fn move_files(
+        request: &TransferRequest,
+        on_progress: impl FnMut(&TransferProgress) + Send,
+    ) -> Result<TransferResult, ApiError>;
+    async fn copy_files(
+        request: &TransferRequest,
+        on_progress: impl FnMut(&TransferProgress) + Send,
+    ) -> Result<TransferResult, ApiError>;
+    /// 把本机的文件或目录上传到target_dir，目录会保持原有结构
+    async fn upload(
+        request: &UploadRequest,
+        token: &CancelToken,
+        on_progress: impl FnMut(&TransferProgress) + Send,
+    ) -> Result<TransferResult, ApiError>;
+    /// 文件的详细信息，音视频附带ffprobe结果，图片附带EXIF
+    async fn get_file_details(path: &str) -> Result<FileDetails, ApiError>;
+    /// 从offset开始读取至多length字节
+    async fn read_file_range(path: &str, offset: u64, length: u64) -> Result<FileChunk, ApiError>;
+    /// 把整个目录下载到下载目录，返回本地的保存位置
+    async fn download_folder(
+        request: &FolderDownloadRequest,
+        token: &CancelToken,
+        on_progress: impl FnMut(&TransferProgress) + Send,
+    ) -> Result<String, ApiError>;
+}
+
+/// 下载到`filename`时使用的`.part`文件，名字中带有来源和路径的哈希，
+/// 同名的不同文件不会共用，同一个文件中断后仍能找到原来的`.part`续传
+pub fn download_part_path(source: &str, path: &str, filename: &str) -> Result<PathBuf, ApiError> {
+    let target = download_target(filename)?;
+    let mut hasher = blake3::Hasher::new();
+    hasher.update(source.as_bytes());
+    hasher.update(&[0]);
+    hasher.update(path.as_bytes());
+    let hash = hasher.finalize().to_hex();
+    Ok(target.with_file_name(format!("{}.{}.part", filename, &hash[..16])))
+}
+
+/// 下载目录中`filename`的位置，filename只能是单个文件名，防止写到下载目录之外
+pub fn download_target(filename: &str) -> Result<PathBuf, ApiError> {
+    if filename.trim().is_empty()
+        || filename == "."
+        || filename == ".."
+        || filename.contains(['/', '\\'])
+    {
+        return Err(ApiError::new(400, format!("无效的保存名称: {}", filename)));
+    }
+    let download_dir =
+        dirs::download_dir().ok_or_else(|| ApiError::new(500, "无法获取下载目录".to_string()))?;
+    Ok(download_dir.join(filename))
 }
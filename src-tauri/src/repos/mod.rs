@@ -7,6 +7,7 @@ pub mod offline;
 pub mod online;
 pub mod thumbnails_repo;
 pub mod transcode_repo;
+pub mod trash_repo;
 
 pub trait Repo {
     type Id;
@@ -0,0 +1,298 @@
+use std::{
+    collections::BTreeMap,
+    fs::File,
+    io::{self, BufReader, Read},
+    path::{Path, PathBuf},
+};
+
+use chrono::NaiveDate;
+use flate2::read::GzDecoder;
+
+use crate::{
+    models::{
+        error::ApiError,
+        files::{FileInfo, FileType},
+    },
+    repos::offline::path_resolver::PathResolver,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ArchiveKind {
+    Zip,
+    Tar,
+    TarGz,
+}
+
+impl ArchiveKind {
+    fn from_name(name: &str) -> Option<Self> {
+        let name = name.to_lowercase();
+        if name.ends_with(".zip") {
+            Some(ArchiveKind::Zip)
+        } else if name.ends_with(".tar") {
+            Some(ArchiveKind::Tar)
+        } else if name.ends_with(".tar.gz") || name.ends_with(".tgz") {
+            Some(ArchiveKind::TarGz)
+        } else {
+            None
+        }
+    }
+}
+
+/// 指向压缩包内部的虚拟路径，如`/backups/photos.zip/2020/a.jpg`
+#[derive(Debug)]
+pub struct ArchiveLocation {
+    pub kind: ArchiveKind,
+    /// 压缩包在磁盘上的真实路径
+    pub archive_path: PathBuf,
+    /// 压缩包本身的虚拟路径
+    pub archive_virtual: String,
+    /// 压缩包内的成员路径，以`/`分隔且没有首尾斜杠，为空表示压缩包根目录
+    pub inner: String,
+}
+
+/// 压缩包中的一个成员
+struct Member {
+    path: String,
+    is_directory: bool,
+    size: u64,
+    last_modified: u64,
+}
+
+impl ArchiveLocation {
+    /// 依次检查名字像压缩包的路径成分，遇到压缩包文件时把剩余部分作为包内路径；
+    /// 路径不经过压缩包时返回None，没有这样的成分时不访问磁盘
+    pub fn locate(resolver: &PathResolver, virtual_path: &str) -> Option<Self> {
+        let parts: Vec<&str> = virtual_path.split('/').filter(|p| !p.is_empty()).collect();
+        for i in 1..=parts.len() {
+            let Some(kind) = ArchiveKind::from_name(parts[i - 1]) else {
+                continue;
+            };
+            let prefix = format!("/{}", parts[..i].join("/"));
+            let real_path = resolver.resolve(&prefix).ok()?;
+            // 名字像压缩包的目录
+            if real_path.is_dir() {
+                continue;
+            }
+            if !real_path.is_file() {
+                return None;
+            }
+            return Some(Self {
+                kind,
+                archive_path: real_path,
+                archive_virtual: prefix,
+                inner: parts[i..].join("/"),
+            });
+        }
+        None
+    }
+
+    /// 列出包内路径下的直接子项，没有显式目录条目的中间目录会被补全
+    pub fn list(&self) -> Result<Vec<FileInfo>, ApiError> {
+        let members = self.read_members()?;
+        let prefix = if self.inner.is_empty() {
+            String::new()
+        } else {
+            format!("{}/", self.inner)
+        };
+
+        if !self.inner.is_empty() {
+            if members
+                .iter()
+                .any(|m| m.path == self.inner && !m.is_directory)
+            {
+                return Err(ApiError::new(400, "指定的路径不是一个目录".to_string()));
+            }
+            if !members
+                .iter()
+                .any(|m| m.path == self.inner || m.path.starts_with(&prefix))
+            {
+                return Err(ApiError::new(404, "压缩包中不存在该路径".to_string()));
+            }
+        }
+
+        // 以名字为键去重，显式的目录条目和推断出的目录合并为一项
+        let mut children: BTreeMap<String, FileInfo> = BTreeMap::new();
+        for member in &members {
+            let Some(rest) = member.path.strip_prefix(&prefix) else {
+                continue;
+            };
+            if rest.is_empty() {
+                continue;
+            }
+
+            let (name, is_nested) = match rest.split_once('/') {
+                Some((name, _)) => (name, true),
+                None => (rest, false),
+            };
+            let is_directory = is_nested || member.is_directory;
+            let path = format!("{}/{}{}", self.archive_virtual, prefix, name);
+
+            let entry = children.entry(name.to_string()).or_insert(FileInfo {
+                name: name.to_string(),
+                size: 0,
+                file_type: FileType::Folder,
+                is_directory,
+                last_modified: member.last_modified,
+                path,
+                tags: Vec::new(),
+                favorite_ids: Vec::new(),
+            });
+            if !is_nested {
+                entry.is_directory = member.is_directory;
+                entry.last_modified = member.last_modified;
+                if !member.is_directory {
+                    entry.size = member.size;
+                    entry.file_type = FileType::from_name(name);
+                }
+            }
+        }
+
+        Ok(children.into_values().collect())
+    }
+
+    /// 把单个成员解压到target，不会展开整个压缩包
+    pub fn extract_to(&self, target: &Path) -> Result<u64, ApiError> {
+        self.with_member_reader(|reader| {
+            let mut writer = File::create(target)?;
+            io::copy(reader, &mut writer)
+        })?
+        .map_err(|e| ApiError::new(500, format!("解压文件失败: {}", e)))
+    }
+
+    /// 成员的文件名，用于推断类型和生成下载文件名
+    pub fn member_name(&self) -> &str {
+        self.inner.rsplit('/').next().unwrap_or_default()
+    }
+
+    fn with_member_reader<T>(&self, f: impl FnOnce(&mut dyn Read) -> T) -> Result<T, ApiError> {
+        if self.inner.is_empty() {
+            return Err(ApiError::new(400, "请指定压缩包中的文件".to_string()));
+        }
+
+        let file = self.open()?;
+        match self.kind {
+            ArchiveKind::Zip => {
+                let mut archive = zip::ZipArchive::new(BufReader::new(file))
+                    .map_err(|e| ApiError::new(500, format!("读取压缩包失败: {}", e)))?;
+                // 成员名可能带有`./`等前缀，按规范化后的路径查找
+                let index = (0..archive.len())
+                    .find(|&i| {
+                        archive
+                            .name_for_index(i)
+                            .is_some_and(|name| normalize_member_path(name) == self.inner)
+                    })
+                    .ok_or_else(|| ApiError::new(404, "压缩包中不存在该文件".to_string()))?;
+                let mut member = archive
+                    .by_index(index)
+                    .map_err(|e| ApiError::new(500, format!("读取压缩包失败: {}", e)))?;
+                if member.is_dir() {
+                    return Err(ApiError::new(400, "指定的路径是一个目录".to_string()));
+                }
+                Ok(f(&mut member))
+            }
+            ArchiveKind::Tar => Self::find_tar_member(BufReader::new(file), &self.inner, f),
+            ArchiveKind::TarGz => {
+                Self::find_tar_member(GzDecoder::new(BufReader::new(file)), &self.inner, f)
+            }
+        }
+    }
+
+    /// tar只能顺序读取，找到目标成员后就停止
+    fn find_tar_member<R: Read, T>(
+        reader: R,
+        inner: &str,
+        f: impl FnOnce(&mut dyn Read) -> T,
+    ) -> Result<T, ApiError> {
+        let mut archive = tar::Archive::new(reader);
+        let entries = archive
+            .entries()
+            .map_err(|e| ApiError::new(500, format!("读取压缩包失败: {}", e)))?;
+        for entry in entries {
+            let mut entry =
+                entry.map_err(|e| ApiError::new(500, format!("读取压缩包失败: {}", e)))?;
+            let is_match = entry
+                .path()
+                .is_ok_and(|p| normalize_member_path(&p.to_string_lossy()) == inner);
+            if is_match && entry.header().entry_type().is_file() {
+                return Ok(f(&mut entry));
+            }
+        }
+        Err(ApiError::new(404, "压缩包中不存在该文件".to_string()))
+    }
+
+    fn read_members(&self) -> Result<Vec<Member>, ApiError> {
+        let file = self.open()?;
+        match self.kind {
+            ArchiveKind::Zip => Self::read_zip_members(file),
+            ArchiveKind::Tar => Self::read_tar_members(BufReader::new(file)),
+            ArchiveKind::TarGz => Self::read_tar_members(GzDecoder::new(BufReader::new(file))),
+        }
+    }
+
+    fn read_zip_members(file: File) -> Result<Vec<Member>, ApiError> {
+        let mut archive = zip::ZipArchive::new(BufReader::new(file))
+            .map_err(|e| ApiError::new(500, format!("读取压缩包失败: {}", e)))?;
+        let mut members = Vec::with_capacity(archive.len());
+        for i in 0..archive.len() {
+            // 只读取元数据，不解压内容
+            let Ok(member) = archive.by_index_raw(i) else {
+                continue;
+            };
+            let last_modified = member
+                .last_modified()
+                .and_then(|t| {
+                    NaiveDate::from_ymd_opt(t.year() as i32, t.month() as u32, t.day() as u32)?
+                        .and_hms_opt(t.hour() as u32, t.minute() as u32, t.second() as u32)
+                })
+                .map(|t| t.and_utc().timestamp().max(0) as u64)
+                .unwrap_or(0);
+            members.push(Member {
+                path: normalize_member_path(member.name()),
+                is_directory: member.is_dir(),
+                size: member.size(),
+                last_modified,
+            });
+        }
+        Ok(members)
+    }
+
+    fn read_tar_members<R: Read>(reader: R) -> Result<Vec<Member>, ApiError> {
+        let mut archive = tar::Archive::new(reader);
+        let entries = archive
+            .entries()
+            .map_err(|e| ApiError::new(500, format!("读取压缩包失败: {}", e)))?;
+        let mut members = Vec::new();
+        for entry in entries {
+            let entry = entry.map_err(|e| ApiError::new(500, format!("读取压缩包失败: {}", e)))?;
+            let header = entry.header();
+            let entry_type = header.entry_type();
+            if !entry_type.is_file() && !entry_type.is_dir() {
+                continue;
+            }
+            let Ok(path) = entry.path() else {
+                continue;
+            };
+            members.push(Member {
+                path: normalize_member_path(&path.to_string_lossy()),
+                is_directory: entry_type.is_dir(),
+                size: entry.size(),
+                last_modified: header.mtime().unwrap_or(0),
+            });
+        }
+        Ok(members)
+    }
+
+    fn open(&self) -> Result<File, ApiError> {
+        File::open(&self.archive_path)
+            .map_err(|e| ApiError::new(500, format!("打开压缩包失败: {}", e)))
+    }
+}
+
+/// 去掉成员路径中的`./`前缀、首尾斜杠和`..`，并统一使用`/`分隔
+fn normalize_member_path(path: &str) -> String {
+    path.replace('\\', "/")
+        .split('/')
+        .filter(|part| !part.is_empty() && *part != "." && *part != "..")
+        .collect::<Vec<_>>()
+        .join("/")
+}
@@ -0,0 +1,181 @@
+use std::{path::Path, sync::Mutex, time::Duration};
+
+use notify_debouncer_full::{
+    new_debouncer,
+    notify::{
+        event::{ModifyKind, RenameMode},
+        EventKind, RecommendedWatcher, RecursiveMode,
+    },
+    DebounceEventResult, DebouncedEvent, Debouncer, RecommendedCache,
+};
+use tauri::{AppHandle, Emitter};
+
+use crate::{
+    models::{
+        error::ApiError,
+        files::{FileChange, FileChangeKind, FilesChangedPayload},
+    },
+    repos::offline::{
+        offline_files_repo::OfflineFilesRepo, path_resolver::PathResolver, OfflineRepo,
+    },
+};
+
+// 去抖间隔，批量写入时合并为一次通知
+const DEBOUNCE_TIMEOUT: Duration = Duration::from_millis(500);
+
+type WatcherHandle = Debouncer<RecommendedWatcher, RecommendedCache>;
+
+// 同一时间只监听前端当前打开的目录
+static CURRENT_WATCHER: Mutex<Option<WatcherHandle>> = Mutex::new(None);
+
+/// 监听离线模式下单个目录的直接子项变化，通过`files-changed`事件推送
+pub struct DirectoryWatcher;
+
+impl OfflineRepo for DirectoryWatcher {}
+
+impl DirectoryWatcher {
+    /// 开始监听path，之前监听的目录会被替换
+    pub fn watch(path: &str, app: &AppHandle) -> Result<(), ApiError> {
+        let resolver = Self::path_resolver()?;
+        let dir = resolver.resolve(path)?;
+        if !dir.is_dir() {
+            return Err(ApiError::new(400, "只能监听目录".to_string()));
+        }
+
+        let app = app.clone();
+        let watched_path = resolver.to_virtual(&dir);
+        let watched_dir = dir.clone();
+        let mut debouncer = new_debouncer(
+            DEBOUNCE_TIMEOUT,
+            None,
+            move |result: DebounceEventResult| match result {
+                Ok(events) => {
+                    let changes = Self::collect_changes(&resolver, &watched_dir, events);
+                    if !changes.is_empty() {
+                        let _ = app.emit(
+                            "files-changed",
+                            FilesChangedPayload {
+                                path: watched_path.clone(),
+                                changes,
+                            },
+                        );
+                    }
+                }
+                Err(errors) => {
+                    for error in errors {
+                        eprintln!("目录监听出错: {}", error);
+                    }
+                }
+            },
+        )
+        .map_err(|e| ApiError::new(500, format!("创建目录监听失败: {}", e)))?;
+
+        debouncer
+            .watch(&dir, RecursiveMode::NonRecursive)
+            .map_err(|e| ApiError::new(500, format!("监听目录失败: {}", e)))?;
+
+        // 旧的监听器在这里被drop，其后台线程随之停止
+        *CURRENT_WATCHER.lock().unwrap() = Some(debouncer);
+        Ok(())
+    }
+
+    /// 停止监听，没有正在监听的目录时返回false
+    pub fn unwatch() -> bool {
+        CURRENT_WATCHER.lock().unwrap().take().is_some()
+    }
+
+    /// 把一批原始事件转换为前端关心的变更，同一路径只保留一条
+    fn collect_changes(
+        resolver: &PathResolver,
+        watched_dir: &Path,
+        events: Vec<DebouncedEvent>,
+    ) -> Vec<FileChange> {
+        let mut changes: Vec<FileChange> = Vec::new();
+        for event in events {
+            let paths: Vec<&Path> = event
+                .paths
+                .iter()
+                .map(|p| p.as_path())
+                .filter(|p| *p != watched_dir && !is_hidden(p))
+                .collect();
+
+            let change = match (event.kind, paths.as_slice()) {
+                (EventKind::Modify(ModifyKind::Name(RenameMode::Both)), [from, to]) => {
+                    Self::change(resolver, FileChangeKind::Renamed, to, Some(from))
+                }
+                (EventKind::Modify(ModifyKind::Name(RenameMode::From)), [path]) => {
+                    Self::change(resolver, FileChangeKind::Removed, path, None)
+                }
+                (EventKind::Modify(ModifyKind::Name(RenameMode::To)), [path]) => {
+                    Self::change(resolver, FileChangeKind::Created, path, None)
+                }
+                // 无法区分重命名的哪一侧时按文件是否还存在判断
+                (EventKind::Modify(ModifyKind::Name(_)), [path]) => {
+                    let kind = if path.symlink_metadata().is_ok() {
+                        FileChangeKind::Created
+                    } else {
+                        FileChangeKind::Removed
+                    };
+                    Self::change(resolver, kind, path, None)
+                }
+                (EventKind::Create(_), [path]) => {
+                    Self::change(resolver, FileChangeKind::Created, path, None)
+                }
+                (EventKind::Modify(_), [path]) => {
+                    Self::change(resolver, FileChangeKind::Modified, path, None)
+                }
+                (EventKind::Remove(_), [path]) => {
+                    Self::change(resolver, FileChangeKind::Removed, path, None)
+                }
+                _ => None,
+            };
+
+            if let Some(change) = change {
+                Self::merge_change(&mut changes, change);
+            }
+        }
+        changes
+    }
+
+    fn change(
+        resolver: &PathResolver,
+        kind: FileChangeKind,
+        path: &Path,
+        old_path: Option<&Path>,
+    ) -> Option<FileChange> {
+        let file = match kind {
+            FileChangeKind::Removed => None,
+            // 文件在去抖期间又被删掉时不再上报
+            _ => {
+                let metadata = path.metadata().ok()?;
+                Some(OfflineFilesRepo::build_file_info(path, &metadata, resolver).ok()?)
+            }
+        };
+
+        Some(FileChange {
+            kind,
+            path: resolver.to_virtual(path),
+            old_path: old_path.map(|p| resolver.to_virtual(p)),
+            file,
+        })
+    }
+
+    /// 新建后又修改的文件仍然算作新建，其余情况以最后一次变更为准
+    fn merge_change(changes: &mut Vec<FileChange>, change: FileChange) {
+        match changes.iter_mut().find(|c| c.path == change.path) {
+            Some(existing)
+                if existing.kind == FileChangeKind::Created
+                    && change.kind == FileChangeKind::Modified =>
+            {
+                existing.file = change.file;
+            }
+            Some(existing) => *existing = change,
+            None => changes.push(change),
+        }
+    }
+}
+
+fn is_hidden(path: &Path) -> bool {
+    path.file_name()
+        .is_some_and(|name| name.to_string_lossy().starts_with('.'))
+}
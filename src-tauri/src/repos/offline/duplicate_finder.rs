@@ -0,0 +1,233 @@
+use std::{
+    collections::{HashMap, HashSet},
+    fs::{File, Metadata},
+    io::Read,
+    path::PathBuf,
+};
+
+use crate::{
+    models::{
+        duplicates::{DuplicateGroup, DuplicateProgress, DuplicateStage},
+        error::ApiError,
+        files::FileInfo,
+    },
+    repos::offline::{
+        file_ops::ProgressReporter, offline_files_repo::OfflineFilesRepo,
+        path_resolver::PathResolver, OfflineRepo,
+    },
+    utils::{blocking, cancellation::CancelToken},
+};
+
+// 大于这个长度的文件先只比较头部，头部不同就不必读完整个文件
+const PARTIAL_HASH_LEN: u64 = 64 * 1024;
+const HASH_BUFFER_SIZE: usize = 1024 * 1024;
+
+/// 在base_dir的子树中查找内容相同的文件
+pub struct DuplicateFinder;
+
+impl OfflineRepo for DuplicateFinder {}
+
+impl DuplicateFinder {
+    /// 先按大小分组，再依次按头部哈希和完整哈希缩小范围，结果按可释放的空间从大到小排序
+    pub async fn find(
+        root: &str,
+        token: &CancelToken,
+        mut on_progress: impl FnMut(&DuplicateProgress) + Send,
+    ) -> Result<Vec<DuplicateGroup>, ApiError> {
+        let root = root.to_string();
+        let token = token.clone();
+        blocking::run_with_events(
+            move |emit| Self::scan(&root, &token, |progress| emit(progress.clone())),
+            |progress: DuplicateProgress| on_progress(&progress),
+        )
+        .await?
+    }
+
+    /// 遍历和计算哈希都在阻塞线程中进行
+    fn scan(
+        root: &str,
+        token: &CancelToken,
+        on_progress: impl FnMut(&DuplicateProgress),
+    ) -> Result<Vec<DuplicateGroup>, ApiError> {
+        let resolver = Self::path_resolver()?;
+        let root = resolver.resolve(root)?;
+        if !root.is_dir() {
+            return Err(ApiError::new(400, "查找的起始路径不是一个目录".to_string()));
+        }
+
+        let mut reporter =
+            ProgressReporter::with_progress(DuplicateProgress::default(), on_progress);
+
+        let mut by_size: HashMap<u64, Vec<PathBuf>> = HashMap::new();
+        // 同一个文件的多个硬链接只算一次，删除其中一个并不能释放空间
+        let mut seen_files = HashSet::new();
+        let mut pending_dirs = vec![root];
+        while let Some(dir) = pending_dirs.pop() {
+            if token.is_cancelled() {
+                return Err(Self::cancelled());
+            }
+            let Ok(entries) = dir.read_dir() else {
+                continue;
+            };
+
+            for entry in entries.flatten() {
+                // 隐藏文件和隐藏目录（包括回收站）都不参与查找
+                if entry.file_name().to_string_lossy().starts_with('.') {
+                    continue;
+                }
+                // 不跟随符号链接，同一个文件不会因为链接被当成重复
+                let Ok(file_type) = entry.file_type() else {
+                    continue;
+                };
+                if file_type.is_dir() {
+                    pending_dirs.push(entry.path());
+                    continue;
+                }
+                let Ok(metadata) = entry.metadata() else {
+                    continue;
+                };
+                if !file_type.is_file() || metadata.len() == 0 {
+                    continue;
+                }
+                if let Some(id) = file_id(&metadata) {
+                    if !seen_files.insert(id) {
+                        continue;
+                    }
+                }
+                reporter.progress.scanned_files += 1;
+                reporter.report(false);
+                by_size
+                    .entry(metadata.len())
+                    .or_default()
+                    .push(entry.path());
+            }
+        }
+
+        let candidates: Vec<(u64, Vec<PathBuf>)> = by_size
+            .into_iter()
+            .filter(|(_, files)| files.len() > 1)
+            .collect();
+        reporter.progress.stage = DuplicateStage::Hashing;
+        reporter.progress.total_files = candidates.iter().map(|(_, f)| f.len() as u64).sum();
+        reporter.progress.total_bytes = candidates
+            .iter()
+            .map(|(size, f)| size * f.len() as u64)
+            .sum();
+        reporter.report(true);
+
+        let mut groups = Vec::new();
+        for (size, files) in candidates {
+            let narrowed = if size > PARTIAL_HASH_LEN {
+                Self::group_by_hash(files, Some(PARTIAL_HASH_LEN), token, &mut reporter)?
+                    .into_values()
+                    .collect()
+            } else {
+                vec![files]
+            };
+
+            for files in narrowed {
+                if files.len() < 2 {
+                    // 头部已经不同的文件直接算作处理完成
+                    reporter.progress.hashed_files += files.len() as u64;
+                    reporter.progress.hashed_bytes += size * files.len() as u64;
+                    continue;
+                }
+                for (hash, files) in Self::group_by_hash(files, None, token, &mut reporter)? {
+                    reporter.progress.hashed_files += files.len() as u64;
+                    reporter.report(false);
+                    if files.len() < 2 {
+                        continue;
+                    }
+                    let files = Self::file_infos(files, &resolver);
+                    if files.len() < 2 {
+                        continue;
+                    }
+                    groups.push(DuplicateGroup { size, hash, files });
+                }
+            }
+        }
+
+        reporter.report(true);
+        groups.sort_by_key(|group| std::cmp::Reverse(group.size * (group.files.len() as u64 - 1)));
+        Ok(groups)
+    }
+
+    /// 只为确认重复的文件构建FileInfo，期间被删除的文件直接剔除
+    fn file_infos(paths: Vec<PathBuf>, resolver: &PathResolver) -> Vec<FileInfo> {
+        let mut files: Vec<FileInfo> = paths
+            .into_iter()
+            .filter_map(|path| {
+                let metadata = path.symlink_metadata().ok()?;
+                OfflineFilesRepo::build_file_info(&path, &metadata, resolver).ok()
+            })
+            .collect();
+        files.sort_by(|a, b| a.path.cmp(&b.path));
+        files
+    }
+
+    /// limit为空时计算完整哈希并计入进度，读取失败的文件直接剔除
+    fn group_by_hash<F: FnMut(&DuplicateProgress)>(
+        files: Vec<PathBuf>,
+        limit: Option<u64>,
+        token: &CancelToken,
+        reporter: &mut ProgressReporter<F, DuplicateProgress>,
+    ) -> Result<HashMap<String, Vec<PathBuf>>, ApiError> {
+        let mut groups: HashMap<String, Vec<PathBuf>> = HashMap::new();
+        for path in files {
+            let Ok(mut file) = File::open(&path) else {
+                continue;
+            };
+            let mut hasher = blake3::Hasher::new();
+            let mut remaining = limit.unwrap_or(u64::MAX);
+            let mut buf = vec![0u8; HASH_BUFFER_SIZE];
+            let mut failed = false;
+
+            while remaining > 0 {
+                if token.is_cancelled() {
+                    return Err(Self::cancelled());
+                }
+                let want = remaining.min(HASH_BUFFER_SIZE as u64) as usize;
+                let read = match file.read(&mut buf[..want]) {
+                    Ok(0) => break,
+                    Ok(read) => read,
+                    Err(_) => {
+                        failed = true;
+                        break;
+                    }
+                };
+                hasher.update(&buf[..read]);
+                remaining -= read as u64;
+                if limit.is_none() {
+                    reporter.progress.hashed_bytes += read as u64;
+                    reporter.report(false);
+                }
+            }
+
+            if !failed {
+                groups
+                    .entry(hasher.finalize().to_hex().to_string())
+                    .or_default()
+                    .push(path);
+            }
+        }
+        Ok(groups)
+    }
+
+    fn cancelled() -> ApiError {
+        ApiError::new(499, "查找已取消".to_string())
+    }
+}
+
+/// 文件所在设备和inode，用于识别硬链接
+#[cfg(unix)]
+fn file_id(metadata: &Metadata) -> Option<(u64, u64)> {
+    use std::os::unix::fs::MetadataExt;
+
+    Some((metadata.dev(), metadata.ino()))
+}
+
+/// 标准库在其他平台上没有稳定的文件标识，不做去重
+#[cfg(not(unix))]
+fn file_id(_metadata: &Metadata) -> Option<(u64, u64)> {
+    None
+}
@@ -0,0 +1,328 @@
+use std::{
+    collections::HashMap,
+    time::{Duration, Instant},
+};
+
+use sqlx::{QueryBuilder, Sqlite};
+use tokio::sync::{mpsc, Mutex};
+
+use crate::{
+    models::{
+        error::ApiError,
+        file_index::{IndexProgress, IndexedFile},
+        files::{FileInfo, SearchQuery},
+    },
+    repos::offline::{
+        offline_files_repo::OfflineFilesRepo, offline_trash_repo::OfflineTrashRepo,
+        path_resolver::PathResolver, Database, OfflineRepo,
+    },
+};
+
+// 每个事务写入的条目数
+const BATCH_SIZE: usize = 500;
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(200);
+
+// 同一时间只允许一个索引任务
+static INDEXING: Mutex<()> = Mutex::const_new(());
+
+// 遍历线程发给写入任务的消息
+enum WalkEvent {
+    Changed(Vec<FileInfo>),
+    Scanned(u64),
+}
+
+/// base_dir下所有条目的sqlite索引，文件名通过FTS5检索
+pub struct FileIndexer;
+
+impl OfflineRepo for FileIndexer {}
+
+impl FileIndexer {
+    /// full为true时清空后重建，否则只重新写入大小或修改时间变化的条目
+    pub async fn rebuild(
+        full: bool,
+        mut on_progress: impl FnMut(&IndexProgress) + Send,
+    ) -> Result<IndexProgress, ApiError> {
+        let Ok(_guard) = INDEXING.try_lock() else {
+            return Err(ApiError::new(409, "索引正在更新中".to_string()));
+        };
+        let pool = Database::get_pool()?;
+        let resolver = Self::path_resolver()?;
+        let base_dir = resolver.base_dir().to_string_lossy().into_owned();
+
+        if full {
+            sqlx::query(r"DELETE FROM file_index WHERE base_dir = ?")
+                .bind(&base_dir)
+                .execute(pool)
+                .await
+                .map_err(|e| ApiError::new(500, format!("清空索引失败: {}", e)))?;
+        }
+
+        // 值为(id, 大小, 修改时间)，遍历过程中移除，剩下的就是已被删除的条目
+        let existing: HashMap<String, (i64, i64, i64)> =
+            sqlx::query_as::<_, (i64, String, i64, i64)>(
+                r"SELECT id, path, size, last_modified FROM file_index WHERE base_dir = ?",
+            )
+            .bind(&base_dir)
+            .fetch_all(pool)
+            .await
+            .map_err(|e| ApiError::new(500, format!("读取索引失败: {}", e)))?
+            .into_iter()
+            .map(|(id, path, size, last_modified)| (path, (id, size, last_modified)))
+            .collect();
+
+        let (tx, mut rx) = mpsc::channel(4);
+        let walk = tokio::task::spawn_blocking(move || Self::walk(resolver, existing, tx));
+
+        let mut progress = IndexProgress::default();
+        while let Some(event) = rx.recv().await {
+            match event {
+                WalkEvent::Changed(files) => {
+                    progress.updated_entries += files.len() as u64;
+                    Self::write_batch(&base_dir, &files).await?;
+                }
+                WalkEvent::Scanned(scanned_entries) => {
+                    progress.scanned_entries = scanned_entries;
+                    on_progress(&progress);
+                }
+            }
+        }
+        let existing = walk
+            .await
+            .map_err(|e| ApiError::new(500, format!("遍历目录失败: {}", e)))?;
+
+        let removed: Vec<i64> = existing.into_values().map(|(id, _, _)| id).collect();
+        for ids in removed.chunks(BATCH_SIZE) {
+            let mut builder = QueryBuilder::<Sqlite>::new("DELETE FROM file_index WHERE id IN (");
+            let mut separated = builder.separated(", ");
+            for id in ids {
+                separated.push_bind(id);
+            }
+            builder.push(")");
+            builder
+                .build()
+                .execute(pool)
+                .await
+                .map_err(|e| ApiError::new(500, format!("删除索引条目失败: {}", e)))?;
+        }
+        progress.removed_entries = removed.len() as u64;
+        progress.finished = true;
+        on_progress(&progress);
+        Ok(progress)
+    }
+
+    /// 在阻塞线程中遍历base_dir，变化的条目按批发送，返回遍历后剩下的已删除条目
+    ///
+    /// 接收端提前退出（如写入失败）时停止遍历
+    fn walk(
+        resolver: PathResolver,
+        mut existing: HashMap<String, (i64, i64, i64)>,
+        tx: mpsc::Sender<WalkEvent>,
+    ) -> HashMap<String, (i64, i64, i64)> {
+        // 回收站和转码缓存是应用自己的数据，不属于用户的文件
+        let excluded = [
+            OfflineTrashRepo::get_trash_dir(resolver.base_dir()),
+            resolver.base_dir().join(".cache"),
+        ];
+        let mut scanned_entries = 0;
+        let mut last_report = Instant::now();
+        let mut changed = Vec::new();
+        let mut pending_dirs = vec![resolver.base_dir().to_path_buf()];
+        while let Some(dir) = pending_dirs.pop() {
+            let Ok(entries) = dir.read_dir() else {
+                continue;
+            };
+            for entry in entries.flatten() {
+                // 不跟随符号链接
+                let Ok(file_type) = entry.file_type() else {
+                    continue;
+                };
+                let path = entry.path();
+                let Ok(metadata) = entry.metadata() else {
+                    continue;
+                };
+                if file_type.is_dir() {
+                    if excluded.contains(&path) {
+                        continue;
+                    }
+                    pending_dirs.push(path.clone());
+                } else if !file_type.is_file() {
+                    continue;
+                }
+
+                scanned_entries += 1;
+                let last_modified = metadata
+                    .modified()
+                    .ok()
+                    .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+                    .map(|d| d.as_secs() as i64)
+                    .unwrap_or(0);
+                let virtual_path = resolver.to_virtual(&path);
+                let unchanged = existing.get(&virtual_path).is_some_and(|(_, size, mtime)| {
+                    *size == metadata.len() as i64 && *mtime == last_modified
+                });
+                // 只有变化的条目才需要判断文件类型，这是增量更新省下的主要开销；
+                // 读取失败的条目留在existing中，旧的索引行会被删除
+                if unchanged {
+                    existing.remove(&virtual_path);
+                } else if let Ok(info) =
+                    OfflineFilesRepo::build_file_info(&path, &metadata, &resolver)
+                {
+                    existing.remove(&virtual_path);
+                    changed.push(info);
+                }
+
+                if changed.len() >= BATCH_SIZE
+                    && tx
+                        .blocking_send(WalkEvent::Changed(std::mem::take(&mut changed)))
+                        .is_err()
+                {
+                    return existing;
+                }
+                if last_report.elapsed() >= PROGRESS_INTERVAL {
+                    if tx
+                        .blocking_send(WalkEvent::Scanned(scanned_entries))
+                        .is_err()
+                    {
+                        return existing;
+                    }
+                    last_report = Instant::now();
+                }
+            }
+        }
+
+        let _ = tx.blocking_send(WalkEvent::Changed(changed));
+        let _ = tx.blocking_send(WalkEvent::Scanned(scanned_entries));
+        existing
+    }
+
+    /// 条件与递归搜索相同，文件名为子串时通过FTS5匹配
+    pub async fn search(query: &SearchQuery) -> Result<Vec<FileInfo>, ApiError> {
+        let pool = Database::get_pool()?;
+        let resolver = Self::path_resolver()?;
+        let base_dir = resolver.base_dir().to_string_lossy().into_owned();
+
+        let mut builder = QueryBuilder::<Sqlite>::new(
+            "SELECT path, name, is_directory, size, last_modified, file_type FROM file_index WHERE base_dir = ",
+        );
+        builder.push_bind(base_dir);
+
+        let root = query.root.trim_end_matches('/');
+        if !root.is_empty() {
+            builder
+                .push(" AND path LIKE ")
+                .push_bind(format!("{}/%", escape_like(root)))
+                .push(r" ESCAPE '\'");
+        }
+
+        if let Some(pattern) = query.pattern.as_deref().filter(|p| !p.is_empty()) {
+            if pattern.contains(['*', '?']) {
+                let like: String = escape_like(pattern)
+                    .chars()
+                    .map(|c| match c {
+                        '*' => '%',
+                        '?' => '_',
+                        c => c,
+                    })
+                    .collect();
+                builder
+                    .push(" AND name LIKE ")
+                    .push_bind(like)
+                    .push(r" ESCAPE '\'");
+            } else if pattern.chars().count() >= 3 {
+                // trigram分词要求查询至少有3个字符
+                builder
+                    .push(
+                        " AND id IN (SELECT rowid FROM file_index_fts WHERE file_index_fts MATCH ",
+                    )
+                    .push_bind(format!("\"{}\"", pattern.replace('"', "\"\"")))
+                    .push(")");
+            } else {
+                builder
+                    .push(" AND name LIKE ")
+                    .push_bind(format!("%{}%", escape_like(pattern)))
+                    .push(r" ESCAPE '\'");
+            }
+        }
+
+        if !query.file_types.is_empty() {
+            builder.push(" AND file_type IN (");
+            let mut separated = builder.separated(", ");
+            for file_type in &query.file_types {
+                separated.push_bind(file_type.to_string());
+            }
+            builder.push(")");
+        }
+        if let Some(min_size) = query.min_size {
+            builder.push(" AND size >= ").push_bind(min_size as i64);
+        }
+        if let Some(max_size) = query.max_size {
+            builder.push(" AND size <= ").push_bind(max_size as i64);
+        }
+        if let Some(after) = query.modified_after {
+            builder
+                .push(" AND last_modified >= ")
+                .push_bind(after as i64);
+        }
+        if let Some(before) = query.modified_before {
+            builder
+                .push(" AND last_modified <= ")
+                .push_bind(before as i64);
+        }
+        builder
+            .push(" ORDER BY is_directory DESC, name LIMIT ")
+            .push_bind(query.limit() as i64);
+
+        let files = builder
+            .build_query_as::<IndexedFile>()
+            .fetch_all(pool)
+            .await
+            .map_err(|e| ApiError::new(500, format!("搜索索引失败: {}", e)))?;
+        Ok(files.into_iter().map(FileInfo::from).collect())
+    }
+
+    async fn write_batch(base_dir: &str, files: &[FileInfo]) -> Result<(), ApiError> {
+        if files.is_empty() {
+            return Ok(());
+        }
+        let mut tx = Database::get_pool()?
+            .begin()
+            .await
+            .map_err(|e| ApiError::new(500, format!("写入索引失败: {}", e)))?;
+        for file in files {
+            sqlx::query(
+                r"
+                INSERT INTO file_index
+                (base_dir, path, name, is_directory, size, last_modified, file_type)
+                VALUES (?, ?, ?, ?, ?, ?, ?)
+                ON CONFLICT(base_dir, path) DO UPDATE SET
+                    name = excluded.name,
+                    is_directory = excluded.is_directory,
+                    size = excluded.size,
+                    last_modified = excluded.last_modified,
+                    file_type = excluded.file_type
+                ",
+            )
+            .bind(base_dir)
+            .bind(&file.path)
+            .bind(&file.name)
+            .bind(file.is_directory)
+            .bind(file.size as i64)
+            .bind(file.last_modified as i64)
+            .bind(file.file_type.to_string())
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| ApiError::new(500, format!("写入索引失败: {}", e)))?;
+        }
+        tx.commit()
+            .await
+            .map_err(|e| ApiError::new(500, format!("写入索引失败: {}", e)))
+    }
+}
+
+/// 转义LIKE中的通配符，配合`ESCAPE '\'`使用
+fn escape_like(value: &str) -> String {
+    value
+        .replace('\\', r"\\")
+        .replace('%', r"\%")
+        .replace('_', r"\_")
+}
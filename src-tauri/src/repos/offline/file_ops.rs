@@ -0,0 +1,429 @@
+use std::{
+    fs::File,
+    io::{ErrorKind, Read, Write},
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use uuid::Uuid;
+
+use crate::{
+    models::{
+        error::ApiError,
+        files::{ConflictPolicy, TransferProgress},
+    },
+    utils::cancellation::CancelToken,
+};
+
+const COPY_BUFFER_SIZE: usize = 1024 * 1024;
+// 两次进度事件之间的最小间隔，避免大文件复制时事件刷屏
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(200);
+
+/// 进度上报器，按时间间隔节流回调，P为进度的类型
+pub struct ProgressReporter<F, P = TransferProgress> {
+    pub progress: P,
+    on_progress: F,
+    last_report: Instant,
+}
+
+impl<F: FnMut(&TransferProgress)> ProgressReporter<F> {
+    pub fn new(total_files: u64, total_bytes: u64, on_progress: F) -> Self {
+        Self::with_progress(
+            TransferProgress {
+                total_files,
+                total_bytes,
+                ..Default::default()
+            },
+            on_progress,
+        )
+    }
+}
+
+impl<F: FnMut(&P), P> ProgressReporter<F, P> {
+    pub fn with_progress(progress: P, on_progress: F) -> Self {
+        Self {
+            progress,
+            on_progress,
+            last_report: Instant::now(),
+        }
+    }
+
+    /// force为true时忽略节流间隔立即上报
+    pub fn report(&mut self, force: bool) {
+        if force || self.last_report.elapsed() >= PROGRESS_INTERVAL {
+            (self.on_progress)(&self.progress);
+            self.last_report = Instant::now();
+        }
+    }
+}
+
+/// 统计路径下的文件数量和总字节数，不跟随符号链接
+pub fn measure(path: &Path) -> (u64, u64) {
+    let Ok(metadata) = path.symlink_metadata() else {
+        return (0, 0);
+    };
+    if metadata.is_file() {
+        return (1, metadata.len());
+    }
+    if !metadata.is_dir() {
+        return (0, 0);
+    }
+
+    let Ok(entries) = path.read_dir() else {
+        return (0, 0);
+    };
+    entries
+        .flatten()
+        .map(|entry| measure(&entry.path()))
+        .fold((0, 0), |(files, bytes), (f, b)| (files + f, bytes + b))
+}
+
+/// 按冲突策略确定的写入目标，覆盖时原有内容要等新内容写好后才删除
+pub struct ConflictTarget {
+    pub path: PathBuf,
+    overwrite: bool,
+}
+
+impl ConflictTarget {
+    /// 调用write把内容写到目标位置，返回最终路径
+    ///
+    /// 需要覆盖时先写到同目录下的临时名字，成功后再换到目标位置并删除旧内容，
+    /// 写入失败时原有的文件保持不变。
+    pub fn write(
+        self,
+        write: impl FnOnce(&Path) -> Result<(), ApiError>,
+    ) -> Result<PathBuf, ApiError> {
+        if !self.overwrite {
+            // 目标原本不存在，写了一半的内容不能留下
+            if let Err(e) = write(&self.path) {
+                let _ = remove_path(&self.path);
+                return Err(e);
+            }
+            return Ok(self.path);
+        }
+
+        let temp_path = sibling_temp_path(&self.path, "tmp");
+        if let Err(e) = write(&temp_path) {
+            let _ = remove_path(&temp_path);
+            return Err(e);
+        }
+
+        let backup_path = sibling_temp_path(&self.path, "old");
+        if let Err(e) = std::fs::rename(&self.path, &backup_path) {
+            let _ = remove_path(&temp_path);
+            return Err(ApiError::new(500, format!("替换已有文件失败: {}", e)));
+        }
+        if let Err(e) = std::fs::rename(&temp_path, &self.path) {
+            let _ = std::fs::rename(&backup_path, &self.path);
+            let _ = remove_path(&temp_path);
+            return Err(ApiError::new(500, format!("替换已有文件失败: {}", e)));
+        }
+        // 新内容已经就位，旧内容删除失败只会留下一个隐藏的临时文件
+        if let Err(e) = remove_path(&backup_path) {
+            eprintln!("删除被覆盖的旧文件失败: {}", e.message);
+        }
+        Ok(self.path)
+    }
+
+    /// 把source直接重命名到目标位置，跨设备无法重命名时返回false，此时应改用write复制
+    ///
+    /// 不能把重命名放进write里：write失败时会删除临时路径，而那里此时放着的是source本身。
+    /// 覆盖时先把已有内容改名备份再重命名，任何一步失败都会换回备份，source保持原样。
+    pub fn rename_from(&self, source: &Path) -> Result<bool, ApiError> {
+        if !self.overwrite {
+            return try_rename(source, &self.path);
+        }
+
+        let backup_path = sibling_temp_path(&self.path, "old");
+        std::fs::rename(&self.path, &backup_path)
+            .map_err(|e| ApiError::new(500, format!("替换已有文件失败: {}", e)))?;
+        match try_rename(source, &self.path) {
+            Ok(true) => {
+                if let Err(e) = remove_path(&backup_path) {
+                    eprintln!("删除被覆盖的旧文件失败: {}", e.message);
+                }
+                Ok(true)
+            }
+            result => {
+                let _ = std::fs::rename(&backup_path, &self.path);
+                result
+            }
+        }
+    }
+
+    /// 把source移动到目标位置，跨设备时复制成功后再删除source
+    pub fn move_from(self, source: &Path) -> Result<PathBuf, ApiError> {
+        if self.rename_from(source)? {
+            return Ok(self.path);
+        }
+        let target = self.write(|path| {
+            let mut reporter = ProgressReporter::new(0, 0, |_: &TransferProgress| {});
+            copy_tree(source, path, &mut reporter, None)
+        })?;
+        remove_path(source)?;
+        Ok(target)
+    }
+}
+
+/// 按冲突策略决定最终写入的目标路径，返回None表示应当跳过
+pub fn resolve_conflict(
+    target: PathBuf,
+    policy: ConflictPolicy,
+) -> Result<Option<ConflictTarget>, ApiError> {
+    if target.symlink_metadata().is_err() {
+        return Ok(Some(ConflictTarget {
+            path: target,
+            overwrite: false,
+        }));
+    }
+
+    match policy {
+        ConflictPolicy::Skip => Ok(None),
+        ConflictPolicy::Overwrite => Ok(Some(ConflictTarget {
+            path: target,
+            overwrite: true,
+        })),
+        ConflictPolicy::Rename => Ok(Some(ConflictTarget {
+            path: next_available_name(&target),
+            overwrite: false,
+        })),
+    }
+}
+
+/// 路径已被占用时返回`name (1).ext`形式的下一个可用路径
+pub fn available_path(path: PathBuf) -> PathBuf {
+    if path.symlink_metadata().is_err() {
+        path
+    } else {
+        next_available_name(&path)
+    }
+}
+
+/// 同一目录下的隐藏临时路径，隐藏文件不会出现在列表中
+pub fn sibling_temp_path(path: &Path, suffix: &str) -> PathBuf {
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    path.with_file_name(format!(".{}.{}.{}", name, Uuid::new_v4(), suffix))
+}
+
+/// 删除文件或整个目录
+pub fn remove_path(path: &Path) -> Result<(), ApiError> {
+    let metadata = path
+        .symlink_metadata()
+        .map_err(|e| ApiError::new(500, format!("获取文件元数据失败: {}", e)))?;
+    let result = if metadata.is_dir() {
+        std::fs::remove_dir_all(path)
+    } else {
+        std::fs::remove_file(path)
+    };
+    result.map_err(|e| ApiError::new(500, format!("删除文件失败: {}", e)))
+}
+
+/// 移动文件或目录，只有跨设备时才退回到复制后删除
+pub fn move_path(source: &Path, target: &Path) -> Result<(), ApiError> {
+    if !try_rename(source, target)? {
+        let mut reporter = ProgressReporter::new(0, 0, |_: &TransferProgress| {});
+        copy_tree(source, target, &mut reporter, None)?;
+        remove_path(source)?;
+    }
+    Ok(())
+}
+
+/// 重命名成功返回true，跨设备无法重命名时返回false，其他错误原样返回
+pub fn try_rename(source: &Path, target: &Path) -> Result<bool, ApiError> {
+    match std::fs::rename(source, target) {
+        Ok(()) => Ok(true),
+        Err(e) if e.kind() == ErrorKind::CrossesDevices => Ok(false),
+        Err(e) => Err(ApiError::new(500, format!("移动文件失败: {}", e))),
+    }
+}
+
+/// 递归复制文件或目录，符号链接会被跳过以免复制到base_dir之外的内容；
+/// 传入token时每个文件和数据块之前都会检查是否已取消
+pub fn copy_tree<F: FnMut(&TransferProgress)>(
+    source: &Path,
+    target: &Path,
+    reporter: &mut ProgressReporter<F>,
+    token: Option<&CancelToken>,
+) -> Result<(), ApiError> {
+    let metadata = source
+        .symlink_metadata()
+        .map_err(|e| ApiError::new(500, format!("获取文件元数据失败: {}", e)))?;
+
+    if metadata.is_dir() {
+        std::fs::create_dir_all(target)
+            .map_err(|e| ApiError::new(500, format!("创建目录失败: {}", e)))?;
+        let entries = source
+            .read_dir()
+            .map_err(|e| ApiError::new(500, format!("读取目录失败: {}", e)))?;
+        for entry in entries {
+            let entry =
+                entry.map_err(|e| ApiError::new(500, format!("读取目录条目失败: {}", e)))?;
+            copy_tree(
+                &entry.path(),
+                &target.join(entry.file_name()),
+                reporter,
+                token,
+            )?;
+        }
+    } else if metadata.is_file() {
+        copy_file(source, target, reporter, token)?;
+        let _ = std::fs::set_permissions(target, metadata.permissions());
+    }
+
+    Ok(())
+}
+
+fn copy_file<F: FnMut(&TransferProgress)>(
+    source: &Path,
+    target: &Path,
+    reporter: &mut ProgressReporter<F>,
+    token: Option<&CancelToken>,
+) -> Result<(), ApiError> {
+    let mut reader =
+        File::open(source).map_err(|e| ApiError::new(500, format!("打开文件失败: {}", e)))?;
+    let mut writer =
+        File::create(target).map_err(|e| ApiError::new(500, format!("创建文件失败: {}", e)))?;
+
+    reporter.progress.current_file = source
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    let mut buf = vec![0u8; COPY_BUFFER_SIZE];
+    loop {
+        if token.is_some_and(CancelToken::is_cancelled) {
+            return Err(ApiError::new(499, "已取消".to_string()));
+        }
+        let read = reader
+            .read(&mut buf)
+            .map_err(|e| ApiError::new(500, format!("读取文件失败: {}", e)))?;
+        if read == 0 {
+            break;
+        }
+        writer
+            .write_all(&buf[..read])
+            .map_err(|e| ApiError::new(500, format!("写入文件失败: {}", e)))?;
+        reporter.progress.copied_bytes += read as u64;
+        reporter.report(false);
+    }
+
+    reporter.progress.copied_files += 1;
+    reporter.report(false);
+    Ok(())
+}
+
+/// 在同一目录下找到第一个不冲突的`name (n).ext`形式的路径
+fn next_available_name(path: &Path) -> PathBuf {
+    let file_name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    // 目录名中的点不视为扩展名
+    let (stem, extension) = match (path.is_dir(), path.file_stem(), path.extension()) {
+        (false, Some(stem), Some(ext)) => (
+            stem.to_string_lossy().into_owned(),
+            Some(ext.to_string_lossy().into_owned()),
+        ),
+        _ => (file_name, None),
+    };
+
+    (1..)
+        .map(|i| match &extension {
+            Some(ext) => path.with_file_name(format!("{} ({}).{}", stem, i, ext)),
+            None => path.with_file_name(format!("{} ({})", stem, i)),
+        })
+        .find(|candidate| candidate.symlink_metadata().is_err())
+        .expect("总能找到一个可用的文件名")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    /// 临时目录，离开作用域时删除
+    struct TempDir(PathBuf);
+
+    impl TempDir {
+        fn new() -> Self {
+            let dir = std::env::temp_dir().join(format!("file-ops-{}", Uuid::new_v4()));
+            fs::create_dir_all(&dir).unwrap();
+            Self(dir)
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn overwriting_move_replaces_the_target() {
+        let dir = TempDir::new();
+        let source = dir.0.join("source.txt");
+        let target = dir.0.join("target.txt");
+        fs::write(&source, b"new").unwrap();
+        fs::write(&target, b"old").unwrap();
+
+        let conflict = resolve_conflict(target.clone(), ConflictPolicy::Overwrite)
+            .unwrap()
+            .unwrap();
+        assert_eq!(conflict.move_from(&source).unwrap(), target);
+        assert!(!source.exists());
+        assert_eq!(fs::read(&target).unwrap(), b"new");
+        // 备份的旧内容已经删除
+        assert_eq!(fs::read_dir(&dir.0).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn failed_backup_keeps_the_moved_source() {
+        let dir = TempDir::new();
+        let source = dir.0.join("source.txt");
+        fs::write(&source, b"only copy").unwrap();
+        // 目标在解决冲突之后被删掉，改名备份时就会失败
+        let conflict = ConflictTarget {
+            path: dir.0.join("target.txt"),
+            overwrite: true,
+        };
+
+        assert!(conflict.move_from(&source).is_err());
+        assert_eq!(fs::read(&source).unwrap(), b"only copy");
+    }
+
+    #[test]
+    fn failed_rename_restores_the_overwritten_target() {
+        let dir = TempDir::new();
+        let target = dir.0.join("target.txt");
+        fs::write(&target, b"old").unwrap();
+        let conflict = resolve_conflict(target.clone(), ConflictPolicy::Overwrite)
+            .unwrap()
+            .unwrap();
+
+        assert!(conflict.rename_from(&dir.0.join("missing.txt")).is_err());
+        assert_eq!(fs::read(&target).unwrap(), b"old");
+        assert_eq!(fs::read_dir(&dir.0).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn failed_write_keeps_the_existing_target() {
+        let dir = TempDir::new();
+        let target = dir.0.join("target.txt");
+        fs::write(&target, b"old").unwrap();
+        let conflict = resolve_conflict(target.clone(), ConflictPolicy::Overwrite)
+            .unwrap()
+            .unwrap();
+
+        let result = conflict.write(|path| {
+            fs::write(path, b"partial").unwrap();
+            Err(ApiError::new(500, "磁盘已满".to_string()))
+        });
+        assert!(result.is_err());
+        assert_eq!(fs::read(&target).unwrap(), b"old");
+        assert_eq!(fs::read_dir(&dir.0).unwrap().count(), 1);
+    }
+}
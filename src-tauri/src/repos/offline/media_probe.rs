@@ -0,0 +1,200 @@
+use std::{collections::HashMap, fs::File, io::BufReader, path::Path};
+
+use exif::{In, Tag, Value};
+use image::ImageReader;
+use serde::Deserialize;
+
+use crate::models::{
+    error::ApiError,
+    file_details::{
+        AudioTrack, GpsCoordinates, ImageDetails, MediaDetails, SubtitleTrack, VideoStream,
+    },
+};
+
+#[derive(Debug, Deserialize)]
+struct FFprobeOutput {
+    #[serde(default)]
+    streams: Vec<FFStream>,
+    format: Option<FFFormat>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FFStream {
+    index: u32,
+    codec_type: Option<String>,
+    codec_name: Option<String>,
+    width: Option<u32>,
+    height: Option<u32>,
+    avg_frame_rate: Option<String>,
+    r_frame_rate: Option<String>,
+    bit_rate: Option<String>,
+    channels: Option<u32>,
+    sample_rate: Option<String>,
+    #[serde(default)]
+    tags: HashMap<String, String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct FFFormat {
+    format_name: Option<String>,
+    duration: Option<String>,
+    bit_rate: Option<String>,
+}
+
+/// 用ffprobe读取音视频的容器和各个流的信息
+pub fn probe_media(path: &Path) -> Result<MediaDetails, ApiError> {
+    let output = std::process::Command::new("ffprobe")
+        .args([
+            "-v",
+            "quiet",
+            "-print_format",
+            "json",
+            "-show_format",
+            "-show_streams",
+        ])
+        .arg(path)
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::piped())
+        .output()
+        .map_err(|e| ApiError::new(500, format!("执行ffprobe失败: {}", e)))?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        return Err(ApiError::new(500, format!("ffprobe错误: {}", stderr)));
+    }
+
+    let probe: FFprobeOutput = serde_json::from_slice(&output.stdout)
+        .map_err(|e| ApiError::new(500, format!("解析ffprobe输出失败: {}", e)))?;
+
+    let mut details = MediaDetails::default();
+    if let Some(format) = probe.format {
+        details.format_name = format.format_name;
+        details.duration = format.duration.and_then(|d| d.parse().ok());
+        details.bit_rate = format.bit_rate.and_then(|b| b.parse().ok());
+    }
+
+    for stream in probe.streams {
+        let codec = stream.codec_name.clone().unwrap_or_default();
+        match stream.codec_type.as_deref() {
+            Some("video") => details.video_streams.push(VideoStream {
+                index: stream.index,
+                codec,
+                width: stream.width,
+                height: stream.height,
+                // 可变帧率的视频avg_frame_rate更准确，缺失时退回r_frame_rate
+                frame_rate: stream
+                    .avg_frame_rate
+                    .as_deref()
+                    .and_then(parse_frame_rate)
+                    .or_else(|| stream.r_frame_rate.as_deref().and_then(parse_frame_rate)),
+                bit_rate: stream.bit_rate.and_then(|b| b.parse().ok()),
+            }),
+            Some("audio") => details.audio_tracks.push(AudioTrack {
+                index: stream.index,
+                codec,
+                channels: stream.channels,
+                sample_rate: stream.sample_rate.and_then(|s| s.parse().ok()),
+                bit_rate: stream.bit_rate.and_then(|b| b.parse().ok()),
+                language: stream.tags.get("language").cloned(),
+                title: stream.tags.get("title").cloned(),
+            }),
+            Some("subtitle") => details.subtitle_tracks.push(SubtitleTrack {
+                index: stream.index,
+                codec,
+                language: stream.tags.get("language").cloned(),
+                title: stream.tags.get("title").cloned(),
+            }),
+            _ => {}
+        }
+    }
+
+    Ok(details)
+}
+
+/// 读取图片尺寸和EXIF信息，读不到的字段留空
+pub fn read_image_details(path: &Path) -> ImageDetails {
+    let mut details = ImageDetails::default();
+    if let Ok((width, height)) = ImageReader::open(path)
+        .and_then(|reader| reader.with_guessed_format())
+        .map_err(image::ImageError::IoError)
+        .and_then(|reader| reader.into_dimensions())
+    {
+        details.width = Some(width);
+        details.height = Some(height);
+    }
+
+    let Ok(file) = File::open(path) else {
+        return details;
+    };
+    let Ok(exif) = exif::Reader::new().read_from_container(&mut BufReader::new(file)) else {
+        return details;
+    };
+
+    let text = |tag: Tag| {
+        exif.get_field(tag, In::PRIMARY)
+            .and_then(|field| match &field.value {
+                Value::Ascii(values) => values
+                    .first()
+                    .map(|v| String::from_utf8_lossy(v).trim().to_string())
+                    .filter(|v| !v.is_empty()),
+                _ => None,
+            })
+    };
+    let uint = |tag: Tag| {
+        exif.get_field(tag, In::PRIMARY)
+            .and_then(|field| field.value.get_uint(0))
+    };
+
+    details.camera_make = text(Tag::Make);
+    details.camera_model = text(Tag::Model);
+    details.date_taken = text(Tag::DateTimeOriginal).or_else(|| text(Tag::DateTime));
+    // image库无法解码的格式（如heic）用EXIF中记录的尺寸
+    details.width = details.width.or_else(|| uint(Tag::PixelXDimension));
+    details.height = details.height.or_else(|| uint(Tag::PixelYDimension));
+
+    let coordinate = |tag: Tag, ref_tag: Tag, negative: &str| {
+        let Value::Rational(parts) = &exif.get_field(tag, In::PRIMARY)?.value else {
+            return None;
+        };
+        let [degrees, minutes, seconds] = parts.as_slice() else {
+            return None;
+        };
+        let value = degrees.to_f64() + minutes.to_f64() / 60.0 + seconds.to_f64() / 3600.0;
+        let is_negative = text(ref_tag).is_some_and(|r| r.eq_ignore_ascii_case(negative));
+        Some(if is_negative { -value } else { value })
+    };
+
+    if let (Some(latitude), Some(longitude)) = (
+        coordinate(Tag::GPSLatitude, Tag::GPSLatitudeRef, "S"),
+        coordinate(Tag::GPSLongitude, Tag::GPSLongitudeRef, "W"),
+    ) {
+        let altitude = exif
+            .get_field(Tag::GPSAltitude, In::PRIMARY)
+            .and_then(|field| match &field.value {
+                Value::Rational(values) => values.first().map(|v| v.to_f64()),
+                _ => None,
+            })
+            .map(|altitude| {
+                // GPSAltitudeRef为1表示海平面以下
+                if uint(Tag::GPSAltitudeRef) == Some(1) {
+                    -altitude
+                } else {
+                    altitude
+                }
+            });
+        details.gps = Some(GpsCoordinates {
+            latitude,
+            longitude,
+            altitude,
+        });
+    }
+
+    details
+}
+
+/// 解析ffprobe中`30000/1001`形式的帧率
+fn parse_frame_rate(rate: &str) -> Option<f64> {
+    let (num, den) = rate.split_once('/')?;
+    let (num, den): (f64, f64) = (num.parse().ok()?, den.parse().ok()?);
+    (num > 0.0 && den > 0.0).then_some(num / den)
+}
@@ -1,18 +1,50 @@
-use std::sync::OnceLock;
+use std::{path::PathBuf, sync::OnceLock};
 
 use sqlx::SqlitePool;
 
-use crate::{commands::config::with_config, models::error::ApiError};
+use crate::{
+    commands::config::{read_base_dir, with_config},
+    models::error::ApiError,
+    repos::offline::path_resolver::PathResolver,
+};
 
+pub mod archive;
+pub mod directory_watcher;
+pub mod duplicate_finder;
+pub mod file_indexer;
+pub mod file_ops;
+pub mod media_probe;
 pub mod offline_favorites_repo;
 pub mod offline_files_repo;
 pub mod offline_thumbnails_repo;
 pub mod offline_transcode_repo;
+pub mod offline_trash_repo;
+pub mod path_resolver;
+pub mod tag_store;
 
 pub trait OfflineRepo {
     fn get_base_dir() -> String {
         with_config(|config| config.base_dir.clone())
     }
+
+    fn path_resolver() -> Result<PathResolver, ApiError> {
+        Ok(PathResolver::new(&Self::get_base_dir())?)
+    }
+
+    /// 只读操作使用的解析器，在线模式下服务器不可用时指向离线镜像
+    fn read_resolver() -> Result<PathResolver, ApiError> {
+        Ok(PathResolver::new(&read_base_dir())?)
+    }
+
+    /// 把虚拟路径解析为base_dir下已存在的真实路径
+    fn resolve_path(virtual_path: &str) -> Result<PathBuf, ApiError> {
+        Ok(Self::path_resolver()?.resolve(virtual_path)?)
+    }
+
+    /// 同resolve_path，但服务器不可用时解析到离线镜像下
+    fn resolve_read_path(virtual_path: &str) -> Result<PathBuf, ApiError> {
+        Ok(Self::read_resolver()?.resolve(virtual_path)?)
+    }
 }
 
 static DB_POOL: OnceLock<SqlitePool> = OnceLock::new();
@@ -1,4 +1,6 @@
-use sqlx::{types::chrono::Utc, SqlitePool};
+use std::collections::HashMap;
+
+use sqlx::{types::chrono::Utc, QueryBuilder, Sqlite, SqlitePool};
 
 use crate::{
     models::{
@@ -30,9 +32,9 @@ impl Repo for OfflineFavoritesRepo {
             r"INSERT INTO favorites(name, created_at, updated_at, sort_order) VALUES(?, ?, ?, ?)",
         )
         .bind(&data.name)
-        .bind(&now)
-        .bind(&now)
-        .bind(&data.sort_order)
+        .bind(now)
+        .bind(now)
+        .bind(data.sort_order)
         .execute(pool)
         .await
         .map_err(|e| ApiError::new(500, format!("创建收藏失败: {}", e)))?;
@@ -90,8 +92,8 @@ impl Repo for OfflineFavoritesRepo {
         // 更新收藏夹信息
         sqlx::query(r"UPDATE favorites SET name = ?, updated_at = ?, sort_order = ? WHERE id = ?")
             .bind(&data.name)
-            .bind(&now)
-            .bind(&data.sort_order)
+            .bind(now)
+            .bind(data.sort_order)
             .bind(id)
             .execute(pool)
             .await
@@ -147,11 +149,11 @@ impl FavoritesRepo for OfflineFavoritesRepo {
             .bind(favorite_id)
             .bind(&request.filename)
             .bind(request.file_size)
-            .bind(&request.file_type.to_string())
+            .bind(request.file_type.to_string())
             .bind(&request.file_path)
             .bind(request.last_modified)
             .bind(request.is_directory)
-            .bind(&now)
+            .bind(now)
             .execute(pool)
             .await
             .map_err(|e| ApiError::new(500, format!("添加文件到收藏夹失败: {}", e)))?;
@@ -189,6 +191,76 @@ impl FavoritesRepo for OfflineFavoritesRepo {
             Err(ApiError::new(404, "未删除任何收藏文件".to_string()))
         }
     }
+
+    async fn get_favorites_for_path(path: &str) -> Result<Vec<FavoriteDto>, ApiError> {
+        let pool = Self::get_pool()?;
+        let files = sqlx::query_as::<_, FavoriteFile>(
+            r"
+            SELECT id, favorite_id, filename, file_size, file_type, file_path,
+                   last_modified, is_directory, created_at
+            FROM favorite_files
+            WHERE file_path = ?
+            ",
+        )
+        .bind(path)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| ApiError::new(500, format!("获取收藏文件失败: {}", e)))?;
+
+        let favorites = sqlx::query_as::<_, Favorite>(
+            r"
+            SELECT id, name, created_at, updated_at, sort_order FROM favorites
+            WHERE id IN (SELECT favorite_id FROM favorite_files WHERE file_path = ?)
+            ORDER BY sort_order
+            ",
+        )
+        .bind(path)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| ApiError::new(500, format!("获取收藏失败: {}", e)))?;
+
+        let files: Vec<FavoriteFileDto> = files.into_iter().map(FavoriteFileDto::from).collect();
+        Ok(favorites
+            .into_iter()
+            .map(|favorite| {
+                let mut dto = FavoriteDto::from(favorite);
+                dto.files = files
+                    .iter()
+                    .filter(|file| file.favorite_id == dto.id)
+                    .cloned()
+                    .collect();
+                dto
+            })
+            .collect())
+    }
+
+    async fn get_favorite_ids_for_paths(
+        paths: &[String],
+    ) -> Result<HashMap<String, Vec<i64>>, ApiError> {
+        let pool = Self::get_pool()?;
+        let mut result: HashMap<String, Vec<i64>> = HashMap::new();
+        // 分批查询，避免超出sqlite的参数数量限制
+        for batch in paths.chunks(500) {
+            let mut builder = QueryBuilder::<Sqlite>::new(
+                "SELECT DISTINCT file_path, favorite_id FROM favorite_files WHERE file_path IN (",
+            );
+            let mut separated = builder.separated(", ");
+            for path in batch {
+                separated.push_bind(path);
+            }
+            builder.push(")");
+
+            let rows: Vec<(String, i64)> = builder
+                .build_query_as()
+                .fetch_all(pool)
+                .await
+                .map_err(|e| ApiError::new(500, format!("获取收藏文件失败: {}", e)))?;
+            for (path, favorite_id) in rows {
+                result.entry(path).or_default().push(favorite_id);
+            }
+        }
+        Ok(result)
+    }
 }
 
 impl OfflineFavoritesRepo {
@@ -1,16 +1,40 @@
 use std::{
-    cmp::Ordering,
+    fs::{File, Metadata},
+    io::{Read, Seek, SeekFrom},
     path::{Path, PathBuf},
 };
 
-use pathdiff::diff_paths;
+use regex::Regex;
 
 use crate::{
+    commands::config::current_source,
     models::{
         error::ApiError,
-        files::{FileInfo, ToFileType},
+        file_details::FileDetails,
+        files::{
+            ConflictPolicy, DeleteSummary, DownloadProgress, FileInfo, FileListing, FileType,
+            FolderDownloadRequest, ListOptions, RenameRequest, SearchQuery, ToFileType,
+            TransferFailure, TransferProgress, TransferRequest, TransferResult, UploadRequest,
+        },
+        preview::FileChunk,
+    },
+    repos::{
+        files_repo::{download_part_path, download_target, FilesRepo},
+        offline::{
+            archive::ArchiveLocation,
+            file_ops::{self, ProgressReporter},
+            media_probe,
+            path_resolver::PathResolver,
+            OfflineRepo,
+        },
+        Repo,
+    },
+    utils::{
+        blocking,
+        cancellation::CancelToken,
+        file_sniff,
+        folder_sink::{finish_part, join_relative, FolderEntry, FolderSink},
     },
-    repos::{files_repo::FilesRepo, offline::OfflineRepo, Repo},
 };
 
 pub struct OfflineFilesRepo;
@@ -22,26 +46,524 @@ impl Repo for OfflineFilesRepo {
     type UpdateRequest = ();
 
     async fn delete(id: Self::Id) -> Result<bool, ApiError> {
-        let path_string = format!("{}{}", &Self::get_base_dir(), id);
-        let file_path = Path::new(&path_string);
-
-        if file_path.exists() {
-            std::fs::remove_file(file_path)
-                .map_err(|e| ApiError::new(500, format!("删除文件失败: {}", e)))?;
-            Ok(true)
-        } else {
-            Err(ApiError::new(404, "文件不存在".to_string()))
+        let file_path = Self::path_resolver()?.resolve_entry(&id)?;
+        if file_path.symlink_metadata().is_ok_and(|m| m.is_dir()) {
+            return Err(ApiError::new(
+                400,
+                "指定的路径是一个目录，请使用删除目录".to_string(),
+            ));
         }
+
+        std::fs::remove_file(file_path)
+            .map_err(|e| ApiError::new(500, format!("删除文件失败: {}", e)))?;
+        Ok(true)
     }
 }
 
 impl OfflineRepo for OfflineFilesRepo {}
 
 impl FilesRepo for OfflineFilesRepo {
-    async fn get_files(path: &str) -> Result<Vec<FileInfo>, ApiError> {
-        let path_string = format!("{}{}", &Self::get_base_dir(), path);
-        let dir_path = Path::new(&path_string);
+    async fn get_files(path: &str, options: &ListOptions) -> Result<FileListing, ApiError> {
+        let resolver = Self::read_resolver()?;
+        let files = match ArchiveLocation::locate(&resolver, path) {
+            Some(location) => location.list()?,
+            None => Self::read_dir_infos(&resolver, path)?,
+        };
+        Ok(options.apply(files))
+    }
+
+    /// 下载文件的实现不适用于离线存储库，都在你硬盘上了下载什么。
+    /// 唯一的例外是压缩包中的文件，它会被单独解压到下载目录
+    async fn download_file(
+        path: &str,
+        filename: &str,
+        mut on_progress: impl FnMut(&DownloadProgress) + Send,
+    ) -> Result<(), ApiError> {
+        let resolver = Self::read_resolver()?;
+        let Some(location) =
+            ArchiveLocation::locate(&resolver, path).filter(|location| !location.inner.is_empty())
+        else {
+            return Err(ApiError::new(
+                400,
+                format!("离线存储库不支持下载文件, {}", path),
+            ));
+        };
+
+        let file_path = download_target(filename)?;
+        let part_path = download_part_path(&current_source(), path, filename)?;
+        let written = match location.extract_to(&part_path) {
+            Ok(written) => written,
+            Err(e) => {
+                let _ = std::fs::remove_file(&part_path);
+                return Err(e);
+            }
+        };
+        finish_part(&part_path, file_path)?;
+
+        on_progress(&DownloadProgress {
+            path: path.to_string(),
+            filename: filename.to_string(),
+            downloaded_bytes: written,
+            total_bytes: Some(written),
+            speed: 0,
+        });
+        Ok(())
+    }
+
+    async fn search(
+        query: &SearchQuery,
+        token: &CancelToken,
+        mut on_found: impl FnMut(&FileInfo) + Send,
+    ) -> Result<Vec<FileInfo>, ApiError> {
+        let resolver = Self::read_resolver()?;
+        let root = resolver.resolve(&query.root)?;
+
+        if !root.is_dir() {
+            return Err(ApiError::new(400, "搜索的起始路径不是一个目录".to_string()));
+        }
+
+        let matcher = NameMatcher::new(query.pattern.as_deref())?;
+        let query = query.clone();
+        let token = token.clone();
+        blocking::run_with_events(
+            move |emit| Self::search_tree(root, &resolver, &matcher, &query, &token, emit),
+            |file_info: FileInfo| on_found(&file_info),
+        )
+        .await
+    }
+
+    async fn create_directory(path: &str) -> Result<FileInfo, ApiError> {
+        let resolver = Self::path_resolver()?;
+        let dir = resolver.resolve_new(path)?;
+        if dir
+            .file_name()
+            .is_some_and(|name| name.to_string_lossy().starts_with('.'))
+        {
+            return Err(ApiError::new(400, "不能创建隐藏目录".to_string()));
+        }
+        if dir.symlink_metadata().is_ok() {
+            return Err(ApiError::new(409, "同名文件或目录已存在".to_string()));
+        }
+
+        std::fs::create_dir(&dir)
+            .map_err(|e| ApiError::new(500, format!("创建目录失败: {}", e)))?;
+        let metadata = dir
+            .symlink_metadata()
+            .map_err(|e| ApiError::new(500, format!("获取文件元数据失败: {}", e)))?;
+        Self::build_file_info(&dir, &metadata, &resolver)
+    }
+
+    async fn delete_directory(path: &str, recursive: bool) -> Result<DeleteSummary, ApiError> {
+        let resolver = Self::path_resolver()?;
+        let dir = resolver.resolve_entry(path)?;
+        if dir == resolver.base_dir() {
+            return Err(ApiError::new(403, "不能删除根目录".to_string()));
+        }
+        // 指向目录的符号链接不算目录，应当按文件删除链接本身
+        if !dir.symlink_metadata().is_ok_and(|m| m.is_dir()) {
+            return Err(ApiError::new(400, "指定的路径不是一个目录".to_string()));
+        }
+
+        if !recursive {
+            std::fs::remove_dir(&dir).map_err(|e| {
+                ApiError::new(400, format!("删除目录失败，非空目录需要递归删除: {}", e))
+            })?;
+            return Ok(DeleteSummary::default());
+        }
+
+        let (removed_files, removed_bytes) = file_ops::measure(&dir);
+        std::fs::remove_dir_all(&dir)
+            .map_err(|e| ApiError::new(500, format!("删除目录失败: {}", e)))?;
+        Ok(DeleteSummary {
+            removed_files,
+            removed_bytes,
+            failed: Vec::new(),
+        })
+    }
+
+    async fn rename(request: &RenameRequest) -> Result<String, ApiError> {
+        let new_name = request.new_name.trim();
+        if new_name.is_empty()
+            || new_name == "."
+            || new_name == ".."
+            || new_name.contains(['/', '\\'])
+        {
+            return Err(ApiError::new(400, "无效的文件名".to_string()));
+        }
+
+        let resolver = Self::path_resolver()?;
+        let source = resolver.resolve_entry(&request.path)?;
+        if source == resolver.base_dir() {
+            return Err(ApiError::new(400, "不能重命名根目录".to_string()));
+        }
+
+        let target = source.with_file_name(new_name);
+        // 大小写不敏感的文件系统上只改大小写时，目标会被视为已存在，此时不能按冲突处理
+        let is_same_file = dunce::canonicalize(&target).is_ok_and(|t| t == source);
+        let target = if is_same_file {
+            std::fs::rename(&source, &target)
+                .map_err(|e| ApiError::new(500, format!("重命名失败: {}", e)))?;
+            target
+        } else {
+            file_ops::resolve_conflict(target, request.conflict)?
+                .ok_or_else(|| ApiError::new(409, format!("目标已存在: {}", new_name)))?
+                .move_from(&source)?
+        };
+        Ok(resolver.to_virtual(&target))
+    }
+
+    async fn move_files(
+        request: &TransferRequest,
+        mut on_progress: impl FnMut(&TransferProgress) + Send,
+    ) -> Result<TransferResult, ApiError> {
+        let request = request.clone();
+        blocking::run_with_events(
+            move |emit| Self::transfer(&request, |progress| emit(progress.clone()), true),
+            |progress: TransferProgress| on_progress(&progress),
+        )
+        .await?
+    }
+
+    async fn copy_files(
+        request: &TransferRequest,
+        mut on_progress: impl FnMut(&TransferProgress) + Send,
+    ) -> Result<TransferResult, ApiError> {
+        let request = request.clone();
+        blocking::run_with_events(
+            move |emit| Self::transfer(&request, |progress| emit(progress.clone()), false),
+            |progress: TransferProgress| on_progress(&progress),
+        )
+        .await?
+    }
+
+    /// 离线模式下的上传即把本机文件导入到base_dir中
+    async fn upload(
+        request: &UploadRequest,
+        token: &CancelToken,
+        mut on_progress: impl FnMut(&TransferProgress) + Send,
+    ) -> Result<TransferResult, ApiError> {
+        let request = request.clone();
+        let token = token.clone();
+        blocking::run_with_events(
+            move |emit| Self::import(&request, &token, |progress| emit(progress.clone())),
+            |progress: TransferProgress| on_progress(&progress),
+        )
+        .await?
+    }
+
+    /// 调用ffprobe和读取EXIF都是阻塞操作，放在阻塞线程中运行
+    async fn get_file_details(path: &str) -> Result<FileDetails, ApiError> {
+        let path = path.to_string();
+        blocking::run(move || Self::read_details(&path)).await?
+    }
+
+    async fn read_file_range(path: &str, offset: u64, length: u64) -> Result<FileChunk, ApiError> {
+        let real_path = Self::resolve_read_path(path)?;
+        let metadata = real_path
+            .metadata()
+            .map_err(|e| ApiError::new(500, format!("获取文件元数据失败: {}", e)))?;
+        if !metadata.is_file() {
+            return Err(ApiError::new(400, "指定的路径不是一个文件".to_string()));
+        }
+
+        let mut file = File::open(&real_path)
+            .map_err(|e| ApiError::new(500, format!("打开文件失败: {}", e)))?;
+        file.seek(SeekFrom::Start(offset))
+            .map_err(|e| ApiError::new(500, format!("定位文件失败: {}", e)))?;
+        let mut data = Vec::new();
+        file.take(length)
+            .read_to_end(&mut data)
+            .map_err(|e| ApiError::new(500, format!("读取文件失败: {}", e)))?;
+
+        Ok(FileChunk::new(offset, data, metadata.len()))
+    }
+
+    async fn download_folder(
+        request: &FolderDownloadRequest,
+        token: &CancelToken,
+        mut on_progress: impl FnMut(&TransferProgress) + Send,
+    ) -> Result<String, ApiError> {
+        let request = request.clone();
+        let token = token.clone();
+        blocking::run_with_events(
+            move |emit| Self::copy_folder(&request, &token, |progress| emit(progress.clone())),
+            |progress: TransferProgress| on_progress(&progress),
+        )
+        .await?
+    }
+}
+
+impl OfflineFilesRepo {
+    fn read_details(path: &str) -> Result<FileDetails, ApiError> {
+        let resolver = Self::read_resolver()?;
+        let real_path = resolver.resolve(path)?;
+        let metadata = real_path
+            .metadata()
+            .map_err(|e| ApiError::new(500, format!("获取文件元数据失败: {}", e)))?;
+        let file = Self::build_file_info(&real_path, &metadata, &resolver)?;
+
+        let created = metadata
+            .created()
+            .ok()
+            .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|d| d.as_secs());
+
+        // 没有安装ffprobe时仍然返回基本信息
+        let media = match file.file_type {
+            FileType::Video | FileType::Audio => match media_probe::probe_media(&real_path) {
+                Ok(media) => Some(media),
+                Err(e) => {
+                    eprintln!("读取媒体信息失败: {}", e.message);
+                    None
+                }
+            },
+            _ => None,
+        };
+        let image = (file.file_type == FileType::Image)
+            .then(|| media_probe::read_image_details(&real_path));
+
+        Ok(FileDetails {
+            created,
+            permissions: Self::format_permissions(&metadata),
+            mime_type: file_sniff::mime_type(&real_path, file.file_type).to_string(),
+            media,
+            image,
+            file,
+        })
+    }
+
+    /// 把本机的文件和目录复制到base_dir中，在阻塞线程中运行
+    fn import(
+        request: &UploadRequest,
+        token: &CancelToken,
+        on_progress: impl FnMut(&TransferProgress),
+    ) -> Result<TransferResult, ApiError> {
+        let resolver = Self::path_resolver()?;
+        let target_dir = resolver.resolve(&request.target_dir)?;
+        if !target_dir.is_dir() {
+            return Err(ApiError::new(400, "目标路径不是一个目录".to_string()));
+        }
 
+        let (total_files, total_bytes) = request
+            .local_paths
+            .iter()
+            .map(|path| file_ops::measure(Path::new(path)))
+            .fold((0, 0), |(files, bytes), (f, b)| (files + f, bytes + b));
+        let mut reporter = ProgressReporter::new(total_files, total_bytes, on_progress);
+        let mut result = TransferResult::default();
+
+        for local_path in &request.local_paths {
+            if token.is_cancelled() {
+                break;
+            }
+
+            let source = Path::new(local_path);
+            let imported = match source.file_name() {
+                // 把目录上传到它自己里面会无限地复制下去
+                Some(_) if dunce::canonicalize(source).is_ok_and(|s| target_dir.starts_with(s)) => {
+                    Err(ApiError::new(400, "不能把目录上传到它自身之内".to_string()))
+                }
+                Some(name) => file_ops::resolve_conflict(target_dir.join(name), request.conflict)
+                    .and_then(|target| match target {
+                        Some(target) => target
+                            .write(|path| {
+                                file_ops::copy_tree(source, path, &mut reporter, Some(token))
+                            })
+                            .map(Some),
+                        None => Ok(None),
+                    }),
+                None => Err(ApiError::new(400, "无效的文件名".to_string())),
+            };
+
+            match imported {
+                // 取消时已经复制的部分被清理掉了，不算失败
+                Err(_) if token.is_cancelled() => break,
+                Ok(Some(target)) => result.completed.push(resolver.to_virtual(&target)),
+                Ok(None) => result.skipped.push(local_path.clone()),
+                Err(e) => result.failed.push(TransferFailure {
+                    path: local_path.clone(),
+                    message: e.message,
+                }),
+            }
+        }
+
+        reporter.report(true);
+        Ok(result)
+    }
+
+    /// 把目录打包或复制到下载目录，在阻塞线程中运行
+    fn copy_folder(
+        request: &FolderDownloadRequest,
+        token: &CancelToken,
+        on_progress: impl FnMut(&TransferProgress),
+    ) -> Result<String, ApiError> {
+        let root = Self::resolve_read_path(&request.path)?;
+        if !root.is_dir() {
+            return Err(ApiError::new(400, "指定的路径不是一个目录".to_string()));
+        }
+
+        let entries = Self::plan_folder(&root)?;
+        let (total_files, total_bytes) =
+            entries
+                .iter()
+                .fold((0, 0), |(files, bytes), entry| match entry {
+                    FolderEntry::File { size, .. } => (files + 1, bytes + size),
+                    FolderEntry::Directory(_) => (files, bytes),
+                });
+        let mut reporter = ProgressReporter::new(total_files, total_bytes, on_progress);
+        let mut sink = FolderSink::create(&request.target_name()?, request.mode)?;
+
+        match Self::write_folder(&entries, &mut sink, token, &mut reporter) {
+            Ok(()) => {
+                let target = sink.finish()?;
+                reporter.report(true);
+                Ok(target.to_string_lossy().into_owned())
+            }
+            Err(e) => {
+                sink.discard();
+                Err(e)
+            }
+        }
+    }
+
+    /// 在阻塞线程中深度优先遍历，找到的条目同时通过emit逐个发出
+    fn search_tree(
+        root: PathBuf,
+        resolver: &PathResolver,
+        matcher: &NameMatcher,
+        query: &SearchQuery,
+        token: &CancelToken,
+        emit: &mut dyn FnMut(FileInfo),
+    ) -> Vec<FileInfo> {
+        let limit = query.limit();
+        let mut results = Vec::new();
+        let mut pending_dirs = vec![root];
+
+        while let Some(dir) = pending_dirs.pop() {
+            if token.is_cancelled() {
+                break;
+            }
+
+            // 无权限等原因读取失败的子目录直接跳过，不中断整个搜索
+            let Ok(entries) = dir.read_dir() else {
+                continue;
+            };
+
+            for entry in entries.flatten() {
+                let path = entry.path();
+                // 不跟随符号链接，避免链接成环
+                let is_dir = entry.file_type().is_ok_and(|t| t.is_dir());
+
+                // 隐藏文件和隐藏目录都不参与搜索
+                let Ok(file_info) = Self::create_file_info(entry, resolver) else {
+                    continue;
+                };
+
+                if is_dir {
+                    pending_dirs.push(path);
+                }
+
+                if matcher.is_match(&file_info.name) && query.matches_attributes(&file_info) {
+                    emit(file_info.clone());
+                    results.push(file_info);
+                    if results.len() >= limit {
+                        return results;
+                    }
+                }
+            }
+        }
+
+        results
+    }
+
+    /// 遍历本地目录得到下载清单，与列表一致跳过隐藏文件，符号链接也会被跳过
+    fn plan_folder(root: &Path) -> Result<Vec<FolderEntry>, ApiError> {
+        let mut entries = Vec::new();
+        let mut pending = vec![(root.to_path_buf(), String::new())];
+        while let Some((dir, relative)) = pending.pop() {
+            let children = dir
+                .read_dir()
+                .map_err(|e| ApiError::new(500, format!("读取目录失败: {}", e)))?;
+            for child in children.flatten() {
+                let name = child.file_name().to_string_lossy().into_owned();
+                let Ok(file_type) = child.file_type() else {
+                    continue;
+                };
+                if name.starts_with('.') {
+                    continue;
+                }
+                let child_relative = join_relative(&relative, &name);
+                if file_type.is_dir() {
+                    entries.push(FolderEntry::Directory(child_relative.clone()));
+                    pending.push((child.path(), child_relative));
+                } else if file_type.is_file() {
+                    entries.push(FolderEntry::File {
+                        source: child.path().to_string_lossy().into_owned(),
+                        relative: child_relative,
+                        size: child.metadata().map(|m| m.len()).unwrap_or(0),
+                    });
+                }
+            }
+        }
+        Ok(entries)
+    }
+
+    fn write_folder<F: FnMut(&TransferProgress)>(
+        entries: &[FolderEntry],
+        sink: &mut FolderSink,
+        token: &CancelToken,
+        reporter: &mut ProgressReporter<F>,
+    ) -> Result<(), ApiError> {
+        let mut buf = vec![0u8; 1024 * 1024];
+        for entry in entries {
+            let (source, relative, size) = match entry {
+                FolderEntry::Directory(relative) => {
+                    sink.add_directory(relative)?;
+                    continue;
+                }
+                FolderEntry::File {
+                    source,
+                    relative,
+                    size,
+                } => (source, relative, *size),
+            };
+
+            reporter.progress.current_file = relative.clone();
+            sink.start_file(relative, size)?;
+            let mut file = File::open(source)
+                .map_err(|e| ApiError::new(500, format!("打开文件失败: {}", e)))?;
+            loop {
+                if token.is_cancelled() {
+                    return Err(ApiError::new(499, "下载已取消".to_string()));
+                }
+                let read = file
+                    .read(&mut buf)
+                    .map_err(|e| ApiError::new(500, format!("读取文件失败: {}", e)))?;
+                if read == 0 {
+                    break;
+                }
+                sink.write(&buf[..read])?;
+                reporter.progress.copied_bytes += read as u64;
+                reporter.report(false);
+            }
+            reporter.progress.copied_files += 1;
+            reporter.report(false);
+        }
+        Ok(())
+    }
+
+    fn create_file_info(
+        entry: std::fs::DirEntry,
+        resolver: &PathResolver,
+    ) -> Result<FileInfo, ApiError> {
+        let metadata = entry
+            .metadata()
+            .map_err(|e| ApiError::new(500, format!("获取文件元数据失败: {}", e)))?;
+        Self::build_file_info(&entry.path(), &metadata, resolver)
+    }
+
+    fn read_dir_infos(resolver: &PathResolver, path: &str) -> Result<Vec<FileInfo>, ApiError> {
+        let dir_path = resolver.resolve(path)?;
         if !dir_path.is_dir() {
             return Err(ApiError::new(400, "指定的路径不是一个目录".to_string()));
         }
@@ -55,31 +577,44 @@ impl FilesRepo for OfflineFilesRepo {
             let entry =
                 entry.map_err(|e| ApiError::new(500, format!("读取目录条目失败: {}", e)))?;
 
-            if let Ok(file_info) = Self::create_file_info(entry) {
+            if let Ok(file_info) = Self::create_file_info(entry, resolver) {
                 files.push(file_info);
             }
         }
-
-        Self::sort_files(&mut files);
         Ok(files)
     }
 
-    /// 下载文件的实现不适用于离线存储库，都在你硬盘上了下载什么。
-    async fn download_file(_path: &str, _filename: &str) -> Result<(), ApiError> {
-        Err(ApiError::new(
-            400,
-            format!("离线存储库不支持下载文件, {}", _path),
-        ))
+    #[cfg(unix)]
+    fn format_permissions(metadata: &Metadata) -> String {
+        use std::os::unix::fs::PermissionsExt;
+
+        let mode = metadata.permissions().mode();
+        (0..9)
+            .rev()
+            .map(|bit| {
+                if mode & (1 << bit) != 0 {
+                    b"rwx"[(8 - bit) % 3] as char
+                } else {
+                    '-'
+                }
+            })
+            .collect()
     }
-}
 
-impl OfflineFilesRepo {
-    fn create_file_info(entry: std::fs::DirEntry) -> Result<FileInfo, ApiError> {
-        let path = entry.path();
-        let metadata = entry
-            .metadata()
-            .map_err(|e| ApiError::new(500, format!("获取文件元数据失败: {}", e)))?;
+    #[cfg(not(unix))]
+    fn format_permissions(metadata: &Metadata) -> String {
+        if metadata.permissions().readonly() {
+            "r--".to_string()
+        } else {
+            "rw-".to_string()
+        }
+    }
 
+    pub fn build_file_info(
+        path: &Path,
+        metadata: &Metadata,
+        resolver: &PathResolver,
+    ) -> Result<FileInfo, ApiError> {
         let file_name = path
             .file_name()
             .and_then(|n| n.to_str())
@@ -97,28 +632,154 @@ impl OfflineFilesRepo {
             .map_err(|e| ApiError::new(500, format!("时间错误: {}", e)))?
             .as_secs();
 
-        let base_dir_path = PathBuf::from(Self::get_base_dir());
         Ok(FileInfo {
             name: file_name.to_string(),
             size: metadata.len(),
             file_type: path.to_file_type(),
             is_directory: metadata.is_dir(),
             last_modified,
-            path: format!(
-                "/{}",
-                diff_paths(&path, &base_dir_path)
-                    .unwrap_or_else(|| base_dir_path)
-                    .to_string_lossy()
-                    .into_owned()
-            ),
+            path: resolver.to_virtual(path),
+            tags: Vec::new(),
+            favorite_ids: Vec::new(),
         })
     }
 
-    fn sort_files(files: &mut Vec<FileInfo>) {
-        files.sort_by(|a, b| match (a.is_directory, b.is_directory) {
-            (true, false) => Ordering::Less,
-            (false, true) => Ordering::Greater,
-            _ => a.name.cmp(&b.name),
-        });
+    /// 移动或复制一批文件，单个条目失败不影响其余条目
+    fn transfer(
+        request: &TransferRequest,
+        on_progress: impl FnMut(&TransferProgress),
+        remove_source: bool,
+    ) -> Result<TransferResult, ApiError> {
+        let resolver = Self::path_resolver()?;
+        let target_dir = resolver.resolve(&request.target_dir)?;
+        if !target_dir.is_dir() {
+            return Err(ApiError::new(400, "目标路径不是一个目录".to_string()));
+        }
+
+        let mut result = TransferResult::default();
+        let mut sources = Vec::new();
+        for path in &request.sources {
+            match resolver.resolve_entry(path) {
+                Ok(source) => sources.push((path, source)),
+                Err(e) => result.failed.push(TransferFailure {
+                    path: path.clone(),
+                    message: e.to_string(),
+                }),
+            }
+        }
+
+        let (total_files, total_bytes) = sources
+            .iter()
+            .map(|(_, source)| file_ops::measure(source))
+            .fold((0, 0), |(files, bytes), (f, b)| (files + f, bytes + b));
+        let mut reporter = ProgressReporter::new(total_files, total_bytes, on_progress);
+
+        for (path, source) in sources {
+            match Self::transfer_one(
+                &resolver,
+                &source,
+                &target_dir,
+                request.conflict,
+                remove_source,
+                &mut reporter,
+            ) {
+                Ok(Some(target)) => result.completed.push(resolver.to_virtual(&target)),
+                Ok(None) => result.skipped.push(path.clone()),
+                Err(e) => result.failed.push(TransferFailure {
+                    path: path.clone(),
+                    message: e.message,
+                }),
+            }
+        }
+
+        reporter.report(true);
+        Ok(result)
+    }
+
+    /// 返回None表示因冲突策略或源与目标相同而跳过
+    fn transfer_one<F: FnMut(&TransferProgress)>(
+        resolver: &PathResolver,
+        source: &Path,
+        target_dir: &Path,
+        conflict: ConflictPolicy,
+        remove_source: bool,
+        reporter: &mut ProgressReporter<F>,
+    ) -> Result<Option<PathBuf>, ApiError> {
+        let file_name = match source.file_name() {
+            Some(name) if source != resolver.base_dir() => name,
+            _ => return Err(ApiError::new(400, "不能移动或复制根目录".to_string())),
+        };
+        if target_dir.starts_with(source) {
+            return Err(ApiError::new(
+                400,
+                "不能把目录移动或复制到它自身之内".to_string(),
+            ));
+        }
+
+        let target = target_dir.join(file_name);
+        if target == source {
+            return Ok(None);
+        }
+        if source.starts_with(&target) {
+            return Err(ApiError::new(400, "目标会覆盖源文件所在的目录".to_string()));
+        }
+        let Some(target) = file_ops::resolve_conflict(target, conflict)? else {
+            return Ok(None);
+        };
+
+        // 同一设备上直接重命名即可，跨设备时退回到复制后删除
+        if remove_source && target.rename_from(source)? {
+            let (files, bytes) = file_ops::measure(&target.path);
+            reporter.progress.copied_files += files;
+            reporter.progress.copied_bytes += bytes;
+            reporter.report(false);
+            return Ok(Some(target.path));
+        }
+        let target = target.write(|path| file_ops::copy_tree(source, path, reporter, None))?;
+        if remove_source {
+            file_ops::remove_path(source)?;
+        }
+        Ok(Some(target))
+    }
+}
+
+/// 文件名匹配器，通配符模式会被转换为正则表达式
+enum NameMatcher {
+    Any,
+    Substring(String),
+    Glob(Regex),
+}
+
+impl NameMatcher {
+    fn new(pattern: Option<&str>) -> Result<Self, ApiError> {
+        let Some(pattern) = pattern.filter(|p| !p.is_empty()) else {
+            return Ok(Self::Any);
+        };
+
+        if !pattern.contains(['*', '?']) {
+            return Ok(Self::Substring(pattern.to_lowercase()));
+        }
+
+        let mut expr = String::from("(?i)^");
+        for c in pattern.chars() {
+            match c {
+                '*' => expr.push_str(".*"),
+                '?' => expr.push('.'),
+                _ => expr.push_str(&regex::escape(c.encode_utf8(&mut [0; 4]))),
+            }
+        }
+        expr.push('$');
+
+        Regex::new(&expr)
+            .map(Self::Glob)
+            .map_err(|e| ApiError::new(400, format!("无效的匹配模式: {}", e)))
+    }
+
+    fn is_match(&self, name: &str) -> bool {
+        match self {
+            Self::Any => true,
+            Self::Substring(sub) => name.to_lowercase().contains(sub.as_str()),
+            Self::Glob(regex) => regex.is_match(name),
+        }
     }
 }
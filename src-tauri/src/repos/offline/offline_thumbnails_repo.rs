@@ -1,6 +1,6 @@
 use std::{io::Cursor, path::Path};
 
-use image::{imageops::FilterType, GenericImageView};
+use image::{imageops::FilterType, GenericImageView, ImageReader};
 use serde::Deserialize;
 
 use crate::{
@@ -8,7 +8,11 @@ use crate::{
         error::ApiError,
         files::{FileType, ToFileType},
     },
-    repos::{offline::OfflineRepo, thumbnails_repo::ThumbnailsRepo, Repo},
+    repos::{
+        offline::{archive::ArchiveLocation, OfflineRepo},
+        thumbnails_repo::ThumbnailsRepo,
+        Repo,
+    },
 };
 
 pub struct OfflineThumbnailsRepo;
@@ -43,13 +47,13 @@ impl Repo for OfflineThumbnailsRepo {
     type UpdateRequest = ();
 
     async fn get(id: Self::Id) -> Result<Self::Item, ApiError> {
-        let path_string = format!("{}{}", &Self::get_base_dir(), id);
-        let file_type = std::path::Path::new(&path_string).to_file_type();
-        match file_type {
-            FileType::Image => Self::get_image_thumbnail(path_string).await,
-            FileType::Video => Self::get_video_thumbnail(path_string).await,
-            _ => Err(ApiError::new(404, "不支持提取缩略图的类型".to_string())),
+        let resolver = Self::read_resolver()?;
+        if let Some(location) = ArchiveLocation::locate(&resolver, &id) {
+            return Self::get_archive_member_thumbnail(&location).await;
         }
+
+        let path = resolver.resolve(&id)?;
+        Self::get_thumbnail_by_type(path.to_string_lossy().into_owned(), path.to_file_type()).await
     }
 }
 
@@ -57,8 +61,12 @@ impl OfflineRepo for OfflineThumbnailsRepo {}
 
 impl ThumbnailsRepo for OfflineThumbnailsRepo {
     async fn get_image_thumbnail(id: Self::Id) -> Result<Self::Item, ApiError> {
-        let img =
-            image::open(id).map_err(|e| ApiError::new(500, format!("打开图片失败: {}", e)))?;
+        // 按文件内容而不是扩展名识别格式
+        let img = ImageReader::open(id)
+            .and_then(|reader| reader.with_guessed_format())
+            .map_err(|e| ApiError::new(500, format!("打开图片失败: {}", e)))?
+            .decode()
+            .map_err(|e| ApiError::new(500, format!("打开图片失败: {}", e)))?;
         let (width, height) = img.dimensions();
 
         let (scaled_width, scaled_height) =
@@ -94,15 +102,63 @@ impl ThumbnailsRepo for OfflineThumbnailsRepo {
         let end_time = duration_secs * 0.9;
         let random_seek_time = rand::random_range(start_time..end_time);
 
+        Self::extract_frame_thumbnail(&id, Some(random_seek_time)).await
+    }
+}
+
+impl OfflineThumbnailsRepo {
+    async fn get_thumbnail_by_type(path: String, file_type: FileType) -> Result<Vec<u8>, ApiError> {
+        match file_type {
+            // heic等image库无法解码的格式交给ffmpeg
+            FileType::Image => match Self::get_image_thumbnail(path.clone()).await {
+                Ok(thumbnail) => Ok(thumbnail),
+                Err(_) => Self::extract_frame_thumbnail(&path, None).await,
+            },
+            FileType::Video => Self::get_video_thumbnail(path).await,
+            _ => Err(ApiError::new(404, "不支持提取缩略图的类型".to_string())),
+        }
+    }
+
+    /// 先把压缩包中的单个文件解压到临时目录，再按普通文件生成缩略图
+    async fn get_archive_member_thumbnail(location: &ArchiveLocation) -> Result<Vec<u8>, ApiError> {
+        let file_type = FileType::from_name(location.member_name());
+        if !matches!(file_type, FileType::Image | FileType::Video) {
+            return Err(ApiError::new(404, "不支持提取缩略图的类型".to_string()));
+        }
+
+        // 保留扩展名，方便ffmpeg识别格式
+        let temp_path = std::env::temp_dir().join(format!(
+            "archive_member_{}_{}",
+            rand::random::<u64>(),
+            location.member_name()
+        ));
+        let result = match location.extract_to(&temp_path) {
+            Ok(_) => {
+                let temp_path_string = temp_path.to_string_lossy().into_owned();
+                Self::get_thumbnail_by_type(temp_path_string, file_type).await
+            }
+            Err(e) => Err(e),
+        };
+
+        let _ = std::fs::remove_file(&temp_path);
+        result
+    }
+
+    /// 用ffmpeg截取一帧再缩放，seek_time为空时取第一帧
+    async fn extract_frame_thumbnail(
+        path: &str,
+        seek_time: Option<f64>,
+    ) -> Result<Vec<u8>, ApiError> {
         let temp_output_path =
             std::env::temp_dir().join(format!("thumbnail_{}.jpg", rand::random::<u64>()));
 
+        let seek_time = seek_time.unwrap_or(0.0).to_string();
         let output = std::process::Command::new("ffmpeg")
             .args([
                 "-ss",
-                &random_seek_time.to_string(),
+                &seek_time,
                 "-i",
-                &id,
+                path,
                 "-vframes",
                 "1",
                 "-q:v",
@@ -127,9 +183,7 @@ impl ThumbnailsRepo for OfflineThumbnailsRepo {
 
         Ok(thumbnail_bytes)
     }
-}
 
-impl OfflineThumbnailsRepo {
     fn get_video_duration_secs(path: &Path) -> Result<f64, ApiError> {
         let output = std::process::Command::new("ffprobe")
             .args([
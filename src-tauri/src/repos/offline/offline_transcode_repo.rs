@@ -42,8 +42,8 @@ impl Repo for OfflineTranscodeRepo {
     type UpdateRequest = ();
 
     async fn create(data: Self::CreateRequest) -> Result<Self::Item, ApiError> {
-        let file_path_string = format!("{}/{}", Self::get_base_dir(), data);
-        let file_path = Path::new(&file_path_string);
+        let file_path = Self::resolve_path(&data)?;
+        let file_path_string = file_path.to_string_lossy().into_owned();
 
         if file_path.to_file_type() != FileType::Video {
             return Err(ApiError::new(400, "文件不是视频格式".to_string()));
@@ -173,7 +173,7 @@ impl OfflineTranscodeRepo {
             "-hls_segment_filename",
             &format!("{}/segment%04d.ts", output_dir.display()),
         ]);
-        command.arg(&playlist_file.to_string_lossy().into_owned());
+        command.arg(playlist_file.to_string_lossy().into_owned());
 
         let child = command
             .stderr(std::process::Stdio::piped())
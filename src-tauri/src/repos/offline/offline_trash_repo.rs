@@ -0,0 +1,282 @@
+use std::path::{Path, PathBuf};
+
+use sqlx::{
+    types::chrono::{DateTime, Utc},
+    SqlitePool,
+};
+use uuid::Uuid;
+
+use crate::{
+    models::{
+        error::ApiError,
+        files::{ConflictPolicy, DeleteSummary, TransferFailure, TransferResult},
+        trash::{TrashItem, TrashItemDto},
+    },
+    repos::{
+        offline::{file_ops, path_resolver::PathResolver, Database, OfflineRepo},
+        trash_repo::TrashRepo,
+        Repo,
+    },
+};
+
+// 回收站目录位于base_dir下，以点开头从而不会出现在列表和搜索中
+const TRASH_DIR_NAME: &str = ".trash";
+
+pub struct OfflineTrashRepo;
+
+impl Repo for OfflineTrashRepo {
+    type Id = i64;
+    type Item = TrashItemDto;
+    type CreateRequest = ();
+    type UpdateRequest = ();
+
+    async fn get_all() -> Result<Vec<Self::Item>, ApiError> {
+        let pool = Self::get_pool()?;
+        let resolver = Self::path_resolver()?;
+        let items = sqlx::query_as::<_, TrashItem>(
+            r"SELECT * FROM trash_items WHERE base_dir = ? ORDER BY deleted_at DESC",
+        )
+        .bind(Self::base_dir_key(&resolver))
+        .fetch_all(pool)
+        .await
+        .map_err(|e| ApiError::new(500, format!("获取回收站失败: {}", e)))?;
+
+        Ok(items.into_iter().map(TrashItemDto::from).collect())
+    }
+}
+
+impl OfflineRepo for OfflineTrashRepo {}
+
+impl TrashRepo for OfflineTrashRepo {
+    async fn move_to_trash(path: &str, recursive: bool) -> Result<TrashItemDto, ApiError> {
+        let pool = Self::get_pool()?;
+        let resolver = Self::path_resolver()?;
+        let source = resolver.resolve_entry(path)?;
+        if source == resolver.base_dir() {
+            return Err(ApiError::new(403, "不能删除根目录".to_string()));
+        }
+
+        let is_directory = source.symlink_metadata().is_ok_and(|m| m.is_dir());
+        if is_directory && !recursive && Self::has_entries(&source)? {
+            return Err(ApiError::new(400, "非空目录需要递归删除".to_string()));
+        }
+
+        let trash_dir = Self::get_trash_dir(resolver.base_dir());
+        std::fs::create_dir_all(&trash_dir)
+            .map_err(|e| ApiError::new(500, format!("创建回收站目录失败: {}", e)))?;
+
+        let trash_name = Uuid::new_v4().to_string();
+        let trash_path = trash_dir.join(&trash_name);
+        let (file_count, file_size) = file_ops::measure(&source);
+        let original_path = resolver.to_virtual(&source);
+        let name = source
+            .file_name()
+            .map(|n| n.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        file_ops::move_path(&source, &trash_path)?;
+
+        let now = Utc::now();
+        let result = sqlx::query(
+            r"
+            INSERT INTO trash_items
+            (base_dir, original_path, name, trash_name, is_directory, file_count, file_size, deleted_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?, ?)
+            ",
+        )
+        .bind(Self::base_dir_key(&resolver))
+        .bind(&original_path)
+        .bind(&name)
+        .bind(&trash_name)
+        .bind(is_directory)
+        .bind(file_count as i64)
+        .bind(file_size as i64)
+        .bind(now)
+        .execute(pool)
+        .await;
+
+        let result = match result {
+            Ok(result) => result,
+            Err(e) => {
+                // 记录写入失败时把文件放回原处，避免文件在回收站里无人认领
+                let _ = file_ops::move_path(&trash_path, &source);
+                return Err(ApiError::new(500, format!("记录回收站条目失败: {}", e)));
+            }
+        };
+
+        Ok(TrashItemDto {
+            id: result.last_insert_rowid(),
+            name,
+            original_path,
+            is_directory,
+            file_count: file_count as i64,
+            file_size: file_size as i64,
+            deleted_at: now.timestamp_millis(),
+        })
+    }
+
+    async fn restore(ids: &[i64], conflict: ConflictPolicy) -> Result<TransferResult, ApiError> {
+        let resolver = Self::path_resolver()?;
+        let mut result = TransferResult::default();
+
+        for &id in ids {
+            let item = match Self::get_item(id).await {
+                Ok(item) => item,
+                Err(e) => {
+                    result.failed.push(TransferFailure {
+                        path: id.to_string(),
+                        message: e.message,
+                    });
+                    continue;
+                }
+            };
+
+            let original_path = item.original_path.clone();
+            match Self::restore_item(&resolver, item, conflict).await {
+                Ok(Some(path)) => result.completed.push(path),
+                Ok(None) => result.skipped.push(original_path),
+                Err(e) => result.failed.push(TransferFailure {
+                    path: original_path,
+                    message: e.message,
+                }),
+            }
+        }
+
+        Ok(result)
+    }
+
+    async fn empty() -> Result<DeleteSummary, ApiError> {
+        let pool = Self::get_pool()?;
+        let resolver = Self::path_resolver()?;
+        let items = sqlx::query_as::<_, TrashItem>(r"SELECT * FROM trash_items WHERE base_dir = ?")
+            .bind(Self::base_dir_key(&resolver))
+            .fetch_all(pool)
+            .await
+            .map_err(|e| ApiError::new(500, format!("获取回收站失败: {}", e)))?;
+
+        Self::purge_items(items).await
+    }
+
+    async fn purge_expired(max_age_days: u32) -> Result<DeleteSummary, ApiError> {
+        let pool = Self::get_pool()?;
+        let max_age_millis = max_age_days as i64 * 24 * 60 * 60 * 1000;
+        let cutoff =
+            DateTime::from_timestamp_millis(Utc::now().timestamp_millis() - max_age_millis)
+                .ok_or_else(|| ApiError::new(500, "计算过期时间失败".to_string()))?;
+        // 过期清理不限于当前的base_dir，每个条目按自己记录的base_dir定位
+        let items =
+            sqlx::query_as::<_, TrashItem>(r"SELECT * FROM trash_items WHERE deleted_at < ?")
+                .bind(cutoff)
+                .fetch_all(pool)
+                .await
+                .map_err(|e| ApiError::new(500, format!("获取过期回收站条目失败: {}", e)))?;
+
+        Self::purge_items(items).await
+    }
+}
+
+impl OfflineTrashRepo {
+    fn get_pool() -> Result<&'static SqlitePool, ApiError> {
+        Database::get_pool()
+    }
+
+    pub fn get_trash_dir(base_dir: &Path) -> PathBuf {
+        base_dir.join(TRASH_DIR_NAME)
+    }
+
+    /// 数据库中记录的base_dir使用规范化后的路径，避免同一目录的不同写法对不上
+    fn base_dir_key(resolver: &PathResolver) -> String {
+        resolver.base_dir().to_string_lossy().into_owned()
+    }
+
+    fn has_entries(dir: &Path) -> Result<bool, ApiError> {
+        let mut entries = dir
+            .read_dir()
+            .map_err(|e| ApiError::new(500, format!("读取目录失败: {}", e)))?;
+        Ok(entries.next().is_some())
+    }
+
+    async fn get_item(id: i64) -> Result<TrashItem, ApiError> {
+        let pool = Self::get_pool()?;
+        sqlx::query_as::<_, TrashItem>(r"SELECT * FROM trash_items WHERE id = ?")
+            .bind(id)
+            .fetch_optional(pool)
+            .await
+            .map_err(|e| ApiError::new(500, format!("获取回收站条目失败: {}", e)))?
+            .ok_or_else(|| ApiError::new(404, "回收站条目不存在".to_string()))
+    }
+
+    async fn delete_record(id: i64) -> Result<(), ApiError> {
+        let pool = Self::get_pool()?;
+        sqlx::query(r"DELETE FROM trash_items WHERE id = ?")
+            .bind(id)
+            .execute(pool)
+            .await
+            .map_err(|e| ApiError::new(500, format!("删除回收站记录失败: {}", e)))?;
+        Ok(())
+    }
+
+    /// 返回None表示因冲突策略被跳过
+    async fn restore_item(
+        resolver: &PathResolver,
+        item: TrashItem,
+        conflict: ConflictPolicy,
+    ) -> Result<Option<String>, ApiError> {
+        if item.base_dir != Self::base_dir_key(resolver) {
+            return Err(ApiError::new(
+                400,
+                "该条目属于其他根目录，请切换后再还原".to_string(),
+            ));
+        }
+
+        let trash_path = Self::get_trash_dir(resolver.base_dir()).join(&item.trash_name);
+        if trash_path.symlink_metadata().is_err() {
+            Self::delete_record(item.id).await?;
+            return Err(ApiError::new(404, "回收站中的文件已丢失".to_string()));
+        }
+
+        resolver.create_parent_dirs(&item.original_path)?;
+        let target = resolver.resolve_new(&item.original_path)?;
+        let Some(target) = file_ops::resolve_conflict(target, conflict)? else {
+            return Ok(None);
+        };
+
+        let target = target.move_from(&trash_path)?;
+        Self::delete_record(item.id).await?;
+        Ok(Some(resolver.to_virtual(&target)))
+    }
+
+    /// 永久删除条目对应的文件和记录，文件已经不存在时只删除记录；
+    /// 单个条目失败时记录原因并继续处理其余条目，文件删除失败的条目保留记录
+    async fn purge_items(items: Vec<TrashItem>) -> Result<DeleteSummary, ApiError> {
+        let mut summary = DeleteSummary::default();
+        for item in items {
+            let trash_path = Self::get_trash_dir(Path::new(&item.base_dir)).join(&item.trash_name);
+            let removed = if trash_path.symlink_metadata().is_ok() {
+                file_ops::remove_path(&trash_path).map(|_| true)
+            } else {
+                Ok(false)
+            };
+            let result = match removed {
+                Ok(removed) => Self::delete_record(item.id).await.map(|_| removed),
+                Err(e) => Err(e),
+            };
+
+            match result {
+                Ok(true) => {
+                    summary.removed_files += item.file_count as u64;
+                    summary.removed_bytes += item.file_size as u64;
+                }
+                Ok(false) => {}
+                Err(e) => {
+                    eprintln!("清理回收站条目{}失败: {}", item.trash_name, e.message);
+                    summary.failed.push(TransferFailure {
+                        path: item.original_path,
+                        message: e.message,
+                    });
+                }
+            }
+        }
+        Ok(summary)
+    }
+}
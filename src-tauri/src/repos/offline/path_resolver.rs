@@ -0,0 +1,296 @@
+use std::path::{Component, Path, PathBuf};
+
+use crate::models::error::ApiError;
+
+/// 虚拟路径解析错误
+#[derive(Debug)]
+pub enum PathError {
+    /// base_dir本身不存在或无法访问
+    BaseDirUnavailable(String),
+    /// 路径不存在
+    NotFound(String),
+    /// 路径（包括通过符号链接）指向了base_dir之外
+    OutsideBaseDir(String),
+    /// 路径格式不合法，如缺少文件名
+    Invalid(String),
+    /// 路径合法，但读写文件系统失败，如没有权限、磁盘已满
+    Io(String, std::io::Error),
+}
+
+impl std::fmt::Display for PathError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PathError::BaseDirUnavailable(msg) => write!(f, "根目录不可用: {}", msg),
+            PathError::NotFound(path) => write!(f, "路径不存在: {}", path),
+            PathError::OutsideBaseDir(path) => write!(f, "路径超出了根目录范围: {}", path),
+            PathError::Invalid(path) => write!(f, "无效的路径: {}", path),
+            PathError::Io(path, e) => write!(f, "访问{}失败: {}", path, e),
+        }
+    }
+}
+
+impl std::error::Error for PathError {}
+
+impl From<PathError> for ApiError {
+    fn from(error: PathError) -> Self {
+        let status_code = match error {
+            PathError::BaseDirUnavailable(_) => 500,
+            PathError::NotFound(_) => 404,
+            PathError::OutsideBaseDir(_) => 403,
+            PathError::Invalid(_) => 400,
+            PathError::Io(..) => 500,
+        };
+        ApiError::new(status_code, error.to_string())
+    }
+}
+
+/// 把形如`/a/b.mp4`的虚拟路径解析为base_dir下的真实路径，所有离线存储库都应通过它访问文件
+pub struct PathResolver {
+    base_dir: PathBuf,
+}
+
+impl PathResolver {
+    pub fn new(base_dir: &str) -> Result<Self, PathError> {
+        let base_dir = dunce::canonicalize(base_dir)
+            .map_err(|e| PathError::BaseDirUnavailable(format!("{}: {}", base_dir, e)))?;
+        Ok(Self { base_dir })
+    }
+
+    /// 规范化后的base_dir
+    pub fn base_dir(&self) -> &Path {
+        &self.base_dir
+    }
+
+    /// 解析已存在的路径，符号链接会被展开后再检查是否越界
+    pub fn resolve(&self, virtual_path: &str) -> Result<PathBuf, PathError> {
+        let joined = self.join(virtual_path)?;
+        let real_path = dunce::canonicalize(&joined)
+            .map_err(|_| PathError::NotFound(virtual_path.to_string()))?;
+        self.ensure_inside(real_path, virtual_path)
+    }
+
+    /// 解析尚不存在的路径（如新建的目录），只要求父目录存在且在base_dir之内
+    pub fn resolve_new(&self, virtual_path: &str) -> Result<PathBuf, PathError> {
+        let joined = self.join(virtual_path)?;
+        if joined == self.base_dir {
+            return Err(PathError::Invalid(virtual_path.to_string()));
+        }
+        let (Some(parent), Some(file_name)) = (joined.parent(), joined.file_name()) else {
+            return Err(PathError::Invalid(virtual_path.to_string()));
+        };
+
+        let real_parent = dunce::canonicalize(parent)
+            .map_err(|_| PathError::NotFound(virtual_path.to_string()))?;
+        let real_parent = self.ensure_inside(real_parent, virtual_path)?;
+        Ok(real_parent.join(file_name))
+    }
+
+    /// 逐级创建虚拟路径缺失的父目录，已存在的最深一级祖先必须在base_dir之内
+    pub fn create_parent_dirs(&self, virtual_path: &str) -> Result<(), PathError> {
+        let joined = self.join(virtual_path)?;
+        let Some(parent) = joined.parent() else {
+            return Ok(());
+        };
+
+        // 先检查已存在的部分，避免通过指向外部的符号链接在base_dir之外创建目录
+        let existing = parent
+            .ancestors()
+            .find(|ancestor| ancestor.exists())
+            .unwrap_or(&self.base_dir);
+        let real_existing = dunce::canonicalize(existing)
+            .map_err(|_| PathError::NotFound(virtual_path.to_string()))?;
+        self.ensure_inside(real_existing, virtual_path)?;
+
+        std::fs::create_dir_all(parent).map_err(|e| PathError::Io(virtual_path.to_string(), e))
+    }
+
+    /// 解析已存在的条目本身，最后一级是符号链接时不展开，用于删除链接而不是链接指向的内容
+    pub fn resolve_entry(&self, virtual_path: &str) -> Result<PathBuf, PathError> {
+        let path = self.resolve_new(virtual_path)?;
+        if path.symlink_metadata().is_err() {
+            return Err(PathError::NotFound(virtual_path.to_string()));
+        }
+        Ok(path)
+    }
+
+    /// 把base_dir下的真实路径转换回以`/`分隔的虚拟路径
+    pub fn to_virtual(&self, real_path: &Path) -> String {
+        let relative = real_path
+            .strip_prefix(&self.base_dir)
+            .unwrap_or(Path::new(""));
+        let parts: Vec<String> = relative
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().into_owned())
+            .collect();
+        format!("/{}", parts.join("/"))
+    }
+
+    /// 逐段拼接虚拟路径，`..`和盘符等会跳出base_dir的组成部分直接拒绝
+    fn join(&self, virtual_path: &str) -> Result<PathBuf, PathError> {
+        let mut path = self.base_dir.clone();
+        for component in Path::new(virtual_path).components() {
+            match component {
+                Component::Normal(part) => path.push(part),
+                Component::RootDir | Component::CurDir => {}
+                Component::ParentDir | Component::Prefix(_) => {
+                    return Err(PathError::OutsideBaseDir(virtual_path.to_string()))
+                }
+            }
+        }
+        Ok(path)
+    }
+
+    fn ensure_inside(&self, real_path: PathBuf, virtual_path: &str) -> Result<PathBuf, PathError> {
+        if real_path.starts_with(&self.base_dir) {
+            Ok(real_path)
+        } else {
+            Err(PathError::OutsideBaseDir(virtual_path.to_string()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    /// 临时目录下的base_dir和与它同级的outside目录，离开作用域时删除
+    struct Fixture {
+        root: PathBuf,
+        resolver: PathResolver,
+    }
+
+    impl Fixture {
+        fn new() -> Self {
+            let root = std::env::temp_dir().join(format!("path-resolver-{}", uuid::Uuid::new_v4()));
+            fs::create_dir_all(root.join("base/dir")).unwrap();
+            fs::create_dir_all(root.join("outside")).unwrap();
+            fs::write(root.join("base/dir/file.txt"), b"inside").unwrap();
+            fs::write(root.join("outside/secret.txt"), b"outside").unwrap();
+            let resolver = PathResolver::new(&root.join("base").to_string_lossy()).unwrap();
+            Self { root, resolver }
+        }
+    }
+
+    impl Drop for Fixture {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.root);
+        }
+    }
+
+    #[test]
+    fn resolves_paths_inside_base_dir() {
+        let fixture = Fixture::new();
+        let resolved = fixture.resolver.resolve("/dir/file.txt").unwrap();
+        assert_eq!(resolved, fixture.resolver.base_dir().join("dir/file.txt"));
+        assert_eq!(fixture.resolver.to_virtual(&resolved), "/dir/file.txt");
+        assert_eq!(
+            fixture.resolver.resolve("/").unwrap(),
+            fixture.resolver.base_dir()
+        );
+    }
+
+    #[test]
+    fn rejects_parent_dir_components() {
+        let fixture = Fixture::new();
+        for path in ["/../outside/secret.txt", "/dir/../../outside", "../base"] {
+            assert!(matches!(
+                fixture.resolver.resolve(path),
+                Err(PathError::OutsideBaseDir(_))
+            ));
+            assert!(matches!(
+                fixture.resolver.resolve_new(path),
+                Err(PathError::OutsideBaseDir(_))
+            ));
+        }
+        // 即使`..`最终仍落在base_dir内也直接拒绝
+        assert!(matches!(
+            fixture.resolver.resolve("/dir/../dir/file.txt"),
+            Err(PathError::OutsideBaseDir(_))
+        ));
+    }
+
+    #[test]
+    fn missing_paths_are_not_found() {
+        let fixture = Fixture::new();
+        assert!(matches!(
+            fixture.resolver.resolve("/missing.txt"),
+            Err(PathError::NotFound(_))
+        ));
+        assert!(matches!(
+            fixture.resolver.resolve_entry("/missing.txt"),
+            Err(PathError::NotFound(_))
+        ));
+    }
+
+    #[test]
+    fn failing_to_create_parent_dirs_is_a_server_error() {
+        let fixture = Fixture::new();
+        // 父目录的位置上已经是一个文件
+        let error = fixture
+            .resolver
+            .create_parent_dirs("/dir/file.txt/sub/new.txt")
+            .unwrap_err();
+        assert!(matches!(error, PathError::Io(..)));
+        assert_eq!(ApiError::from(error).status_code, 500);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn rejects_symlinks_escaping_base_dir() {
+        use std::os::unix::fs::symlink;
+
+        let fixture = Fixture::new();
+        let base_dir = fixture.resolver.base_dir().to_path_buf();
+        symlink(fixture.root.join("outside"), base_dir.join("escape")).unwrap();
+        symlink(
+            fixture.root.join("outside/secret.txt"),
+            base_dir.join("secret-link"),
+        )
+        .unwrap();
+
+        for path in ["/escape", "/escape/secret.txt", "/secret-link"] {
+            assert!(matches!(
+                fixture.resolver.resolve(path),
+                Err(PathError::OutsideBaseDir(_))
+            ));
+        }
+        // 不能通过指向外部的目录链接在base_dir之外新建或创建目录
+        assert!(matches!(
+            fixture.resolver.resolve_new("/escape/new.txt"),
+            Err(PathError::OutsideBaseDir(_))
+        ));
+        assert!(matches!(
+            fixture.resolver.create_parent_dirs("/escape/a/b/new.txt"),
+            Err(PathError::OutsideBaseDir(_))
+        ));
+        assert!(!fixture.root.join("outside/a").exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn resolve_entry_keeps_the_link_itself() {
+        use std::os::unix::fs::symlink;
+
+        let fixture = Fixture::new();
+        let base_dir = fixture.resolver.base_dir().to_path_buf();
+        symlink(
+            fixture.root.join("outside/secret.txt"),
+            base_dir.join("secret-link"),
+        )
+        .unwrap();
+        symlink(base_dir.join("dir/file.txt"), base_dir.join("file-link")).unwrap();
+
+        // 删除或重命名的是链接本身，不会作用到base_dir之外的目标
+        assert_eq!(
+            fixture.resolver.resolve_entry("/secret-link").unwrap(),
+            base_dir.join("secret-link")
+        );
+        // 指向base_dir内部的链接可以正常展开
+        assert_eq!(
+            fixture.resolver.resolve("/file-link").unwrap(),
+            base_dir.join("dir/file.txt")
+        );
+    }
+}
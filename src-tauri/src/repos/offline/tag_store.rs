@@ -0,0 +1,285 @@
+use std::collections::{HashMap, HashSet};
+
+use sqlx::{QueryBuilder, Sqlite};
+
+use crate::{
+    commands::config::current_source,
+    models::{error::ApiError, files::FileInfo, tag::TagDto},
+    repos::offline::Database,
+    utils::tag_expr::TagExpr,
+};
+
+// 单条语句中IN列表的最大长度
+const IN_BATCH_SIZE: usize = 500;
+
+/// 保存在本机sqlite中的文件标签，在线和离线模式都可用，按current_source区分
+pub struct TagStore;
+
+impl TagStore {
+    pub async fn list() -> Result<Vec<TagDto>, ApiError> {
+        sqlx::query_as::<_, TagDto>(
+            r"
+            SELECT t.id, t.name, COUNT(ft.path) AS file_count
+            FROM tags t
+            LEFT JOIN file_tags ft ON ft.tag_id = t.id AND ft.source = ?
+            GROUP BY t.id
+            ORDER BY t.name COLLATE NOCASE
+            ",
+        )
+        .bind(current_source())
+        .fetch_all(Database::get_pool()?)
+        .await
+        .map_err(|e| ApiError::new(500, format!("获取标签失败: {}", e)))
+    }
+
+    /// 标签不存在时自动创建，已有的标签不会重复添加
+    pub async fn tag(paths: &[String], tags: &[String]) -> Result<(), ApiError> {
+        let tags = Self::normalize_tags(tags)?;
+        let source = current_source();
+        let mut tx = Database::get_pool()?
+            .begin()
+            .await
+            .map_err(|e| ApiError::new(500, format!("添加标签失败: {}", e)))?;
+
+        for tag in &tags {
+            sqlx::query(r"INSERT INTO tags (name) VALUES (?) ON CONFLICT(name) DO NOTHING")
+                .bind(tag)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| ApiError::new(500, format!("创建标签失败: {}", e)))?;
+            let (tag_id,): (i64,) = sqlx::query_as(r"SELECT id FROM tags WHERE name = ?")
+                .bind(tag)
+                .fetch_one(&mut *tx)
+                .await
+                .map_err(|e| ApiError::new(500, format!("创建标签失败: {}", e)))?;
+
+            for path in paths {
+                sqlx::query(
+                    r"INSERT OR IGNORE INTO file_tags (tag_id, source, path) VALUES (?, ?, ?)",
+                )
+                .bind(tag_id)
+                .bind(&source)
+                .bind(path)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| ApiError::new(500, format!("添加标签失败: {}", e)))?;
+            }
+        }
+
+        tx.commit()
+            .await
+            .map_err(|e| ApiError::new(500, format!("添加标签失败: {}", e)))
+    }
+
+    /// 移除标签后不再被任何文件使用的标签会被一并删除
+    pub async fn untag(paths: &[String], tags: &[String]) -> Result<u64, ApiError> {
+        let tags = Self::normalize_tags(tags)?;
+        let source = current_source();
+        let mut tx = Database::get_pool()?
+            .begin()
+            .await
+            .map_err(|e| ApiError::new(500, format!("移除标签失败: {}", e)))?;
+
+        let mut removed = 0;
+        for tag in &tags {
+            for path in paths {
+                removed += sqlx::query(
+                    r"
+                    DELETE FROM file_tags
+                    WHERE source = ? AND path = ? AND tag_id = (SELECT id FROM tags WHERE name = ?)
+                    ",
+                )
+                .bind(&source)
+                .bind(path)
+                .bind(tag)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e| ApiError::new(500, format!("移除标签失败: {}", e)))?
+                .rows_affected();
+            }
+        }
+        sqlx::query(r"DELETE FROM tags WHERE id NOT IN (SELECT tag_id FROM file_tags)")
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| ApiError::new(500, format!("清理标签失败: {}", e)))?;
+
+        tx.commit()
+            .await
+            .map_err(|e| ApiError::new(500, format!("移除标签失败: {}", e)))?;
+        Ok(removed)
+    }
+
+    /// 按标签表达式查找路径，只在带有标签的文件中查找，返回路径及其全部标签
+    pub async fn query(expr: &TagExpr) -> Result<Vec<(String, Vec<String>)>, ApiError> {
+        let rows: Vec<(String, String)> = sqlx::query_as(
+            r"
+            SELECT ft.path, t.name
+            FROM file_tags ft
+            JOIN tags t ON t.id = ft.tag_id
+            WHERE ft.source = ?
+            ORDER BY ft.path, t.name COLLATE NOCASE
+            ",
+        )
+        .bind(current_source())
+        .fetch_all(Database::get_pool()?)
+        .await
+        .map_err(|e| ApiError::new(500, format!("查询标签失败: {}", e)))?;
+
+        let mut grouped: Vec<(String, Vec<String>)> = Vec::new();
+        for (path, tag) in rows {
+            match grouped.last_mut() {
+                Some((last, tags)) if *last == path => tags.push(tag),
+                _ => grouped.push((path, vec![tag])),
+            }
+        }
+
+        Ok(grouped
+            .into_iter()
+            .filter(|(_, tags)| {
+                let lowercase: HashSet<String> = tags.iter().map(|t| t.to_lowercase()).collect();
+                expr.matches(&lowercase)
+            })
+            .collect())
+    }
+
+    /// 把标签填入文件列表，失败时保持列表不变
+    pub async fn attach(files: &mut [FileInfo]) {
+        if files.is_empty() {
+            return;
+        }
+        match Self::tags_for(files.iter().map(|f| f.path.as_str())).await {
+            Ok(mut tags) => {
+                for file in files {
+                    file.tags = tags.remove(&file.path).unwrap_or_default();
+                }
+            }
+            Err(e) => eprintln!("读取文件标签失败: {}", e.message),
+        }
+    }
+
+    /// 回收站中的条目没有可见的路径，它的标签暂存在这个虚拟路径下，还原时再移回
+    pub fn trash_path(trash_id: i64) -> String {
+        format!("/.trash/{}", trash_id)
+    }
+
+    /// 文件或目录改名、移动后把标签跟到新路径，目录下所有文件的标签一并更新；失败时只记录日志
+    pub async fn relocate(from: &str, to: &str) {
+        if let Err(e) = Self::try_relocate(from, to).await {
+            eprintln!("更新{}的标签失败: {}", from, e.message);
+        }
+    }
+
+    /// 文件或目录被永久删除后删除它及其下所有文件的标签；失败时只记录日志
+    pub async fn forget(path: &str) {
+        if let Err(e) = Self::try_forget(path).await {
+            eprintln!("删除{}的标签失败: {}", path, e.message);
+        }
+    }
+
+    async fn try_relocate(from: &str, to: &str) -> Result<(), ApiError> {
+        if from == to {
+            return Ok(());
+        }
+        let prefix = Self::descendant_prefix(from);
+        // 目标路径已经有同一个标签时保留一条
+        sqlx::query(
+            r"
+            UPDATE OR REPLACE file_tags SET path = ? || substr(path, ?)
+            WHERE source = ? AND (path = ? OR substr(path, 1, ?) = ?)
+            ",
+        )
+        .bind(to)
+        .bind(from.chars().count() as i64 + 1)
+        .bind(current_source())
+        .bind(from)
+        .bind(prefix.chars().count() as i64)
+        .bind(&prefix)
+        .execute(Database::get_pool()?)
+        .await
+        .map_err(|e| ApiError::new(500, format!("更新标签失败: {}", e)))?;
+        Ok(())
+    }
+
+    async fn try_forget(path: &str) -> Result<(), ApiError> {
+        let prefix = Self::descendant_prefix(path);
+        let mut tx = Database::get_pool()?
+            .begin()
+            .await
+            .map_err(|e| ApiError::new(500, format!("删除标签失败: {}", e)))?;
+        sqlx::query(
+            r"DELETE FROM file_tags WHERE source = ? AND (path = ? OR substr(path, 1, ?) = ?)",
+        )
+        .bind(current_source())
+        .bind(path)
+        .bind(prefix.chars().count() as i64)
+        .bind(&prefix)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| ApiError::new(500, format!("删除标签失败: {}", e)))?;
+        sqlx::query(r"DELETE FROM tags WHERE id NOT IN (SELECT tag_id FROM file_tags)")
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| ApiError::new(500, format!("清理标签失败: {}", e)))?;
+        tx.commit()
+            .await
+            .map_err(|e| ApiError::new(500, format!("删除标签失败: {}", e)))
+    }
+
+    /// path下所有文件路径共同的前缀
+    fn descendant_prefix(path: &str) -> String {
+        format!("{}/", path.trim_end_matches('/'))
+    }
+
+    async fn tags_for<'a>(
+        paths: impl Iterator<Item = &'a str>,
+    ) -> Result<HashMap<String, Vec<String>>, ApiError> {
+        let pool = Database::get_pool()?;
+        let source = current_source();
+        let paths: Vec<&str> = paths.collect();
+        let mut result: HashMap<String, Vec<String>> = HashMap::new();
+
+        for batch in paths.chunks(IN_BATCH_SIZE) {
+            let mut builder = QueryBuilder::<Sqlite>::new(
+                "SELECT ft.path, t.name FROM file_tags ft JOIN tags t ON t.id = ft.tag_id WHERE ft.source = ",
+            );
+            builder.push_bind(&source).push(" AND ft.path IN (");
+            let mut separated = builder.separated(", ");
+            for path in batch {
+                separated.push_bind(*path);
+            }
+            builder.push(") ORDER BY t.name COLLATE NOCASE");
+
+            let rows: Vec<(String, String)> = builder
+                .build_query_as()
+                .fetch_all(pool)
+                .await
+                .map_err(|e| ApiError::new(500, format!("读取文件标签失败: {}", e)))?;
+            for (path, tag) in rows {
+                result.entry(path).or_default().push(tag);
+            }
+        }
+        Ok(result)
+    }
+
+    /// 去掉首尾空白和重复项，双引号会与表达式语法冲突因此不允许出现
+    fn normalize_tags(tags: &[String]) -> Result<Vec<String>, ApiError> {
+        let mut seen = HashSet::new();
+        let mut normalized = Vec::new();
+        for tag in tags {
+            let tag = tag.trim();
+            if tag.is_empty() {
+                continue;
+            }
+            if tag.contains('"') {
+                return Err(ApiError::new(400, "标签名不能包含双引号".to_string()));
+            }
+            if seen.insert(tag.to_lowercase()) {
+                normalized.push(tag.to_string());
+            }
+        }
+        if normalized.is_empty() {
+            return Err(ApiError::new(400, "标签名不能为空".to_string()));
+        }
+        Ok(normalized)
+    }
+}
@@ -4,6 +4,7 @@ pub mod online_favorites_repo;
 pub mod online_files_repo;
 pub mod online_thumbnails_repo;
 pub mod online_transcode_repo;
+pub mod online_trash_repo;
 
 pub trait OnlineRepo: Repo {
     fn get_server_url() -> String {
@@ -1,3 +1,9 @@
+use std::{
+    collections::HashMap,
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
 use crate::{
     models::{
         error::ApiError,
@@ -12,6 +18,35 @@ use crate::{
 
 pub struct OnlineFavoritesRepo;
 
+/// 路径到所在收藏夹id的映射，避免每次列目录都拉取全部收藏
+struct FavoriteIdsCache {
+    server_url: String,
+    fetched_at: Instant,
+    ids: HashMap<String, Vec<i64>>,
+}
+
+// 其他客户端修改的收藏最多延迟这么久才能看到，本机的修改会立即让缓存失效
+const FAVORITE_IDS_TTL: Duration = Duration::from_secs(30);
+
+static FAVORITE_IDS_CACHE: Mutex<Option<FavoriteIdsCache>> = Mutex::new(None);
+
+impl OnlineFavoritesRepo {
+    fn invalidate_favorite_ids() {
+        *FAVORITE_IDS_CACHE.lock().unwrap() = None;
+    }
+
+    fn cached_favorite_ids(server_url: &str) -> Option<HashMap<String, Vec<i64>>> {
+        FAVORITE_IDS_CACHE
+            .lock()
+            .unwrap()
+            .as_ref()
+            .filter(|cache| {
+                cache.server_url == server_url && cache.fetched_at.elapsed() < FAVORITE_IDS_TTL
+            })
+            .map(|cache| cache.ids.clone())
+    }
+}
+
 impl Repo for OnlineFavoritesRepo {
     type Id = i64;
     type Item = FavoriteDto;
@@ -28,7 +63,9 @@ impl Repo for OnlineFavoritesRepo {
 
     async fn delete(id: i64) -> Result<bool, ApiError> {
         let endpoint = format!("favorites/{}", id);
-        api_delete_success(&Self::get_server_url(), &endpoint).await
+        let result = api_delete_success(&Self::get_server_url(), &endpoint).await;
+        Self::invalidate_favorite_ids();
+        result
     }
 
     async fn update(id: Self::Id, data: Self::UpdateRequest) -> Result<Self::Item, ApiError> {
@@ -45,7 +82,9 @@ impl FavoritesRepo for OnlineFavoritesRepo {
         favorite_id: i64,
     ) -> Result<bool, ApiError> {
         let endpoint = format!("favorites/{}/files", favorite_id);
-        api_post_success(&Self::get_server_url(), &endpoint, &request).await
+        let result = api_post_success(&Self::get_server_url(), &endpoint, &request).await;
+        Self::invalidate_favorite_ids();
+        result
     }
 
     async fn get_all_favorite_files() -> Result<Vec<FavoriteFileDto>, ApiError> {
@@ -54,6 +93,48 @@ impl FavoritesRepo for OnlineFavoritesRepo {
 
     async fn delete_favorite_file(id: i64) -> Result<bool, ApiError> {
         let endpoint = format!("favorites/files/{}", id);
-        api_delete_success(&Self::get_server_url(), &endpoint).await
+        let result = api_delete_success(&Self::get_server_url(), &endpoint).await;
+        Self::invalidate_favorite_ids();
+        result
+    }
+
+    /// 服务端没有按路径查询的接口，在全部收藏中筛选
+    async fn get_favorites_for_path(path: &str) -> Result<Vec<FavoriteDto>, ApiError> {
+        let favorites = Self::get_all().await?;
+        Ok(favorites
+            .into_iter()
+            .filter_map(|mut favorite| {
+                favorite.files.retain(|file| file.file_path == path);
+                (!favorite.files.is_empty()).then_some(favorite)
+            })
+            .collect())
+    }
+
+    /// 服务端没有按路径查询的接口，全部收藏会缓存一小段时间
+    async fn get_favorite_ids_for_paths(
+        paths: &[String],
+    ) -> Result<HashMap<String, Vec<i64>>, ApiError> {
+        let server_url = Self::get_server_url();
+        let mut all_ids = match Self::cached_favorite_ids(&server_url) {
+            Some(ids) => ids,
+            None => {
+                let mut ids: HashMap<String, Vec<i64>> = HashMap::new();
+                for file in Self::get_all_favorite_files().await? {
+                    ids.entry(file.file_path)
+                        .or_default()
+                        .push(file.favorite_id);
+                }
+                *FAVORITE_IDS_CACHE.lock().unwrap() = Some(FavoriteIdsCache {
+                    server_url,
+                    fetched_at: Instant::now(),
+                    ids: ids.clone(),
+                });
+                ids
+            }
+        };
+        Ok(paths
+            .iter()
+            .filter_map(|path| all_ids.remove_entry(path))
+            .collect())
     }
 }
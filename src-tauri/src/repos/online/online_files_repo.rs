@@ -1,12 +1,52 @@
-use std::fs;
+use std::{
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use reqwest::StatusCode;
+use tokio::{
+    fs::{self, File, OpenOptions},
+    io::{AsyncReadExt, AsyncWriteExt},
+    time::sleep,
+};
 
 use crate::services::api_service::api_delete_success;
 use crate::{
-    models::{error::ApiError, files::FileInfo},
-    repos::{files_repo::FilesRepo, online::OnlineRepo, Repo},
-    services::api_service::{api_get, api_get_bytes},
+    commands::config::current_source,
+    models::{
+        error::ApiError,
+        file_details::FileDetails,
+        files::{
+            ConflictPolicy, DeleteSummary, DownloadProgress, FileInfo, FileListing,
+            FileListingResponse, FolderDownloadMode, FolderDownloadRequest, ListOptions,
+            RenameRequest, SearchQuery, TransferFailure, TransferProgress, TransferRequest,
+            TransferResult, UploadInitRequest, UploadRequest, UploadSession,
+        },
+        preview::FileChunk,
+    },
+    repos::{
+        files_repo::{download_part_path, download_target, FilesRepo},
+        offline::file_ops::{self, ProgressReporter},
+        online::OnlineRepo,
+        Repo,
+    },
+    services::api_service::{
+        api_delete, api_get, api_get_range, api_get_stream, api_post, api_post_bytes, encode_query,
+    },
+    utils::{
+        cancellation::CancelToken,
+        folder_sink::{finish_part, join_relative, zip_paths, FolderEntry, FolderSink},
+    },
 };
 
+// 网络中断后的最大重试次数
+const DOWNLOAD_MAX_RETRIES: u32 = 5;
+const DOWNLOAD_RETRY_DELAY: Duration = Duration::from_secs(2);
+// 两次下载进度事件之间的最小间隔
+const DOWNLOAD_PROGRESS_INTERVAL: Duration = Duration::from_millis(500);
+// 上传时每个分块的大小
+const UPLOAD_CHUNK_SIZE: usize = 4 * 1024 * 1024;
+
 pub struct OnlineFilesRepo;
 
 impl Repo for OnlineFilesRepo {
@@ -24,21 +64,633 @@ impl Repo for OnlineFilesRepo {
 impl OnlineRepo for OnlineFilesRepo {}
 
 impl FilesRepo for OnlineFilesRepo {
-    async fn get_files(path: &str) -> Result<Vec<FileInfo>, ApiError> {
-        let endpoint = format!("files?path={}", path);
+    async fn get_files(path: &str, options: &ListOptions) -> Result<FileListing, ApiError> {
+        let endpoint = format!(
+            "files?path={}{}",
+            encode_query(path),
+            options.to_query_string()
+        );
+        match api_get(&Self::get_server_url(), &endpoint).await? {
+            FileListingResponse::Paged(listing) => Ok(listing),
+            // 旧版本的服务端忽略排序和分页参数，在本地处理
+            FileListingResponse::Legacy(files) => Ok(options.apply(files)),
+        }
+    }
+    /// 先流式写入`.part`文件，中断后用Range请求续传，完成后才重命名为目标文件
+    async fn download_file(
+        path: &str,
+        filename: &str,
+        mut on_progress: impl FnMut(&DownloadProgress) + Send,
+    ) -> Result<(), ApiError> {
+        let file_path = download_target(filename)?;
+        let part_path = download_part_path(&current_source(), path, filename)?;
+
+        let mut progress = DownloadProgress {
+            path: path.to_string(),
+            filename: filename.to_string(),
+            downloaded_bytes: 0,
+            total_bytes: None,
+            speed: 0,
+        };
+
+        let mut attempt = 0;
+        loop {
+            match Self::download_to_part(&part_path, &mut progress, &mut on_progress).await {
+                Ok(()) => break,
+                // 416说明本地的.part文件已经不可续传，删掉后从头下载
+                Err(e) if e.status_code == 416 && attempt < DOWNLOAD_MAX_RETRIES => {
+                    let _ = fs::remove_file(&part_path).await;
+                }
+                Err(e) if Self::is_retryable(&e) && attempt < DOWNLOAD_MAX_RETRIES => {
+                    eprintln!("下载中断，准备第{}次重试: {}", attempt + 1, e.message);
+                    sleep(DOWNLOAD_RETRY_DELAY * (attempt + 1)).await;
+                }
+                Err(e) => return Err(e),
+            }
+            attempt += 1;
+        }
+
+        // 已有同名文件时不覆盖，改存为下一个可用的名字
+        finish_part(&part_path, file_path)?;
+
+        progress.speed = 0;
+        on_progress(&progress);
+        Ok(())
+    }
+
+    /// 服务端没有可以逐条返回结果、中途取消的搜索接口
+    async fn search(
+        _query: &SearchQuery,
+        _token: &CancelToken,
+        _on_found: impl FnMut(&FileInfo) + Send,
+    ) -> Result<Vec<FileInfo>, ApiError> {
+        Err(ApiError::new(
+            501,
+            "在线模式不支持搜索，请切换到离线模式后再搜索".to_string(),
+        ))
+    }
+
+    async fn create_directory(path: &str) -> Result<FileInfo, ApiError> {
+        let endpoint = format!("files/directory?path={}", encode_query(path));
+        api_post(&Self::get_server_url(), &endpoint, &()).await
+    }
+
+    async fn delete_directory(path: &str, recursive: bool) -> Result<DeleteSummary, ApiError> {
+        let endpoint = format!(
+            "files/directory?path={}&recursive={}",
+            encode_query(path),
+            recursive
+        );
+        api_delete(&Self::get_server_url(), &endpoint).await
+    }
+
+    async fn rename(request: &RenameRequest) -> Result<String, ApiError> {
+        api_post(&Self::get_server_url(), "files/rename", request).await
+    }
+
+    async fn move_files(
+        request: &TransferRequest,
+        on_progress: impl FnMut(&TransferProgress) + Send,
+    ) -> Result<TransferResult, ApiError> {
+        Self::transfer_each("files/move", request, on_progress).await
+    }
+
+    async fn copy_files(
+        request: &TransferRequest,
+        on_progress: impl FnMut(&TransferProgress) + Send,
+    ) -> Result<TransferResult, ApiError> {
+        Self::transfer_each("files/copy", request, on_progress).await
+    }
+
+    /// 每个文件先申请上传会话，再按分块依次发送，最后由服务端合并
+    async fn upload(
+        request: &UploadRequest,
+        token: &CancelToken,
+        mut on_progress: impl FnMut(&TransferProgress) + Send,
+    ) -> Result<TransferResult, ApiError> {
+        let mut progress = TransferProgress::default();
+        let mut result = TransferResult::default();
+
+        for local_path in &request.local_paths {
+            let (files, bytes) = file_ops::measure(Path::new(local_path));
+            progress.total_files += files;
+            progress.total_bytes += bytes;
+        }
+
+        for local_path in &request.local_paths {
+            let source = Path::new(local_path);
+            let Some(name) = source.file_name() else {
+                result.failed.push(TransferFailure {
+                    path: local_path.clone(),
+                    message: "无效的文件名".to_string(),
+                });
+                continue;
+            };
+            let remote_root = join_remote(&request.target_dir, &name.to_string_lossy());
+
+            for item in Self::plan_upload(source, &remote_root) {
+                if token.is_cancelled() {
+                    return Ok(result);
+                }
+                match item {
+                    UploadItem::Directory(remote) => match Self::create_directory(&remote).await {
+                        // 目录已存在时直接往里面上传
+                        Ok(_) => {}
+                        Err(e) if e.status_code == 409 => {}
+                        Err(e) => result.failed.push(TransferFailure {
+                            path: remote,
+                            message: e.message,
+                        }),
+                    },
+                    UploadItem::File {
+                        local,
+                        remote,
+                        size,
+                    } => {
+                        let local_display = local.to_string_lossy().into_owned();
+                        match Self::upload_file(
+                            &local,
+                            &remote,
+                            size,
+                            request.conflict,
+                            token,
+                            &mut progress,
+                            &mut on_progress,
+                        )
+                        .await
+                        {
+                            Ok(Some(path)) => result.completed.push(path),
+                            Ok(None) => result.skipped.push(local_display),
+                            Err(e) if token.is_cancelled() => {
+                                eprintln!("上传已取消: {}", e.message);
+                                return Ok(result);
+                            }
+                            Err(e) => result.failed.push(TransferFailure {
+                                path: local_display,
+                                message: e.message,
+                            }),
+                        }
+                        progress.copied_files += 1;
+                        on_progress(&progress);
+                    }
+                }
+            }
+        }
+
+        Ok(result)
+    }
+
+    async fn get_file_details(path: &str) -> Result<FileDetails, ApiError> {
+        let endpoint = format!("files/details?path={}", encode_query(path));
         api_get(&Self::get_server_url(), &endpoint).await
     }
-    async fn download_file(path: &str, filename: &str) -> Result<(), ApiError> {
-        let endpoint = format!("files/download?path={}", path);
-        let bytes = api_get_bytes(&Self::get_server_url(), &endpoint).await?;
 
-        let download_dir = dirs::download_dir()
-            .ok_or_else(|| ApiError::network("无法获取下载目录".to_string()))?;
-        let file_path = download_dir.join(&filename);
+    /// 复用下载接口，通过Range请求只取需要的部分
+    async fn read_file_range(path: &str, offset: u64, length: u64) -> Result<FileChunk, ApiError> {
+        let endpoint = format!("files/download?path={}", encode_query(path));
+        let (data, total_size) =
+            api_get_range(&Self::get_server_url(), &endpoint, offset, length).await?;
+        let total_size = total_size.unwrap_or(offset + data.len() as u64);
+        Ok(FileChunk::new(offset, data, total_size))
+    }
+
+    /// 打包下载时优先让服务端生成zip，服务端不支持时遍历目录逐个下载后在本地打包
+    async fn download_folder(
+        request: &FolderDownloadRequest,
+        token: &CancelToken,
+        on_progress: impl FnMut(&TransferProgress) + Send,
+    ) -> Result<String, ApiError> {
+        let name = request.target_name()?;
+        let mut reporter = ProgressReporter::new(0, 0, on_progress);
+        if request.mode == FolderDownloadMode::Zip {
+            match Self::download_server_zip(&request.path, &name, token, &mut reporter).await {
+                Err(e) if matches!(e.status_code, 404 | 405 | 501) => {}
+                result => return result,
+            }
+        }
+
+        let entries = Self::plan_folder(&request.path, token).await?;
+        for entry in &entries {
+            if let FolderEntry::File { size, .. } = entry {
+                reporter.progress.total_files += 1;
+                reporter.progress.total_bytes += size;
+            }
+        }
+        let mut sink = FolderSink::create(&name, request.mode)?;
+
+        match Self::write_folder(&entries, &mut sink, token, &mut reporter).await {
+            Ok(()) => {
+                let target = sink.finish()?;
+                reporter.report(true);
+                Ok(target.to_string_lossy().into_owned())
+            }
+            Err(e) => {
+                sink.discard();
+                Err(e)
+            }
+        }
+    }
+}
+
+impl OnlineFilesRepo {
+    /// 逐个条目请求服务端移动或复制，每完成一个条目上报一次进度；
+    /// 单个条目的失败记录在结果中，不影响其余条目
+    async fn transfer_each(
+        endpoint: &str,
+        request: &TransferRequest,
+        mut on_progress: impl FnMut(&TransferProgress) + Send,
+    ) -> Result<TransferResult, ApiError> {
+        let server_url = Self::get_server_url();
+        let mut progress = TransferProgress {
+            total_files: request.sources.len() as u64,
+            ..Default::default()
+        };
+        let mut result = TransferResult::default();
+
+        for source in &request.sources {
+            progress.current_file = source.clone();
+            let single = TransferRequest {
+                sources: vec![source.clone()],
+                target_dir: request.target_dir.clone(),
+                conflict: request.conflict,
+            };
+            match api_post::<TransferResult, _>(&server_url, endpoint, &single).await {
+                Ok(item) => {
+                    result.completed.extend(item.completed);
+                    result.skipped.extend(item.skipped);
+                    result.failed.extend(item.failed);
+                }
+                Err(e) => result.failed.push(TransferFailure {
+                    path: source.clone(),
+                    message: e.message,
+                }),
+            }
+            progress.copied_files += 1;
+            on_progress(&progress);
+        }
+        Ok(result)
+    }
+
+    /// 从`.part`文件已有的长度开始下载剩余部分
+    async fn download_to_part(
+        part_path: &Path,
+        progress: &mut DownloadProgress,
+        on_progress: &mut (impl FnMut(&DownloadProgress) + Send),
+    ) -> Result<(), ApiError> {
+        let offset = fs::metadata(part_path).await.map(|m| m.len()).unwrap_or(0);
+        let endpoint = format!("files/download?path={}", encode_query(&progress.path));
+        let mut response = api_get_stream(&Self::get_server_url(), &endpoint, offset).await?;
+
+        // 服务端不支持Range时会返回200和完整内容，此时只能从头写
+        let resumed = offset > 0 && response.status() == StatusCode::PARTIAL_CONTENT;
+        let start = if resumed { offset } else { 0 };
+        progress.downloaded_bytes = start;
+        progress.total_bytes = response.content_length().map(|len| len + start);
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .append(resumed)
+            .truncate(!resumed)
+            .open(part_path)
+            .await
+            .map_err(|e| ApiError::new(500, format!("创建临时文件失败: {}", e)))?;
+
+        let mut last_report = Instant::now();
+        let mut bytes_since_report = 0u64;
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|e| ApiError::network(format!("读取文件内容失败: {}", e)))?
+        {
+            file.write_all(&chunk)
+                .await
+                .map_err(|e| ApiError::new(500, format!("写入临时文件失败: {}", e)))?;
+            progress.downloaded_bytes += chunk.len() as u64;
+            bytes_since_report += chunk.len() as u64;
+
+            let elapsed = last_report.elapsed();
+            if elapsed >= DOWNLOAD_PROGRESS_INTERVAL {
+                progress.speed = (bytes_since_report as f64 / elapsed.as_secs_f64()) as u64;
+                on_progress(progress);
+                last_report = Instant::now();
+                bytes_since_report = 0;
+            }
+        }
+
+        file.flush()
+            .await
+            .map_err(|e| ApiError::new(500, format!("写入临时文件失败: {}", e)))?;
+
+        if progress
+            .total_bytes
+            .is_some_and(|total| progress.downloaded_bytes < total)
+        {
+            return Err(ApiError::network("下载连接意外中断".to_string()));
+        }
+        Ok(())
+    }
+
+    /// 把服务端生成的zip直接保存到下载目录
+    async fn download_server_zip<F: FnMut(&TransferProgress)>(
+        path: &str,
+        name: &str,
+        token: &CancelToken,
+        reporter: &mut ProgressReporter<F>,
+    ) -> Result<String, ApiError> {
+        let endpoint = format!("files/download/zip?path={}", encode_query(path));
+        let mut response = api_get_stream(&Self::get_server_url(), &endpoint, 0).await?;
+
+        let (file_path, part_path) = zip_paths(name)?;
+        reporter.progress.current_file = format!("{}.zip", name);
+        reporter.progress.total_files = 1;
+        reporter.progress.total_bytes = response.content_length().unwrap_or(0);
 
-        fs::write(&file_path, &bytes)
-            .map_err(|e| ApiError::network(format!("保存文件失败: {}", e)))?;
+        let mut file = File::create(&part_path)
+            .await
+            .map_err(|e| ApiError::new(500, format!("创建临时文件失败: {}", e)))?;
+        let written: Result<(), ApiError> = async {
+            while let Some(chunk) = response
+                .chunk()
+                .await
+                .map_err(|e| ApiError::network(format!("读取文件内容失败: {}", e)))?
+            {
+                if token.is_cancelled() {
+                    return Err(ApiError::new(499, "下载已取消".to_string()));
+                }
+                file.write_all(&chunk)
+                    .await
+                    .map_err(|e| ApiError::new(500, format!("写入临时文件失败: {}", e)))?;
+                reporter.progress.copied_bytes += chunk.len() as u64;
+                reporter.report(false);
+            }
+            file.flush()
+                .await
+                .map_err(|e| ApiError::new(500, format!("写入临时文件失败: {}", e)))
+        }
+        .await;
 
+        drop(file);
+        if let Err(e) = written {
+            let _ = fs::remove_file(&part_path).await;
+            return Err(e);
+        }
+        let file_path = finish_part(&part_path, file_path)?;
+
+        reporter.progress.copied_files = 1;
+        reporter.report(true);
+        Ok(file_path.to_string_lossy().into_owned())
+    }
+
+    /// 用get_files逐层遍历服务端目录得到下载清单
+    async fn plan_folder(root: &str, token: &CancelToken) -> Result<Vec<FolderEntry>, ApiError> {
+        let mut entries = Vec::new();
+        let mut pending = vec![(root.to_string(), String::new())];
+        while let Some((path, relative)) = pending.pop() {
+            if token.is_cancelled() {
+                return Err(ApiError::new(499, "下载已取消".to_string()));
+            }
+            let listing = Self::get_files(&path, &ListOptions::default()).await?;
+            for file in listing.files {
+                let child_relative = join_relative(&relative, &file.name);
+                if file.is_directory {
+                    entries.push(FolderEntry::Directory(child_relative.clone()));
+                    pending.push((file.path, child_relative));
+                } else {
+                    entries.push(FolderEntry::File {
+                        source: file.path,
+                        relative: child_relative,
+                        size: file.size,
+                    });
+                }
+            }
+        }
+        Ok(entries)
+    }
+
+    async fn write_folder<F: FnMut(&TransferProgress)>(
+        entries: &[FolderEntry],
+        sink: &mut FolderSink,
+        token: &CancelToken,
+        reporter: &mut ProgressReporter<F>,
+    ) -> Result<(), ApiError> {
+        let server_url = Self::get_server_url();
+        for entry in entries {
+            let (source, relative, size) = match entry {
+                FolderEntry::Directory(relative) => {
+                    sink.add_directory(relative)?;
+                    continue;
+                }
+                FolderEntry::File {
+                    source,
+                    relative,
+                    size,
+                } => (source, relative, *size),
+            };
+
+            reporter.progress.current_file = relative.clone();
+            sink.start_file(relative, size)?;
+            let mut written = 0;
+            let mut attempt = 0;
+            loop {
+                let result = Self::stream_folder_file(
+                    &server_url,
+                    source,
+                    &mut written,
+                    sink,
+                    token,
+                    reporter,
+                )
+                .await;
+                match result {
+                    Ok(()) => break,
+                    Err(e) if Self::is_retryable(&e) && attempt < DOWNLOAD_MAX_RETRIES => {
+                        eprintln!(
+                            "下载{}中断，准备第{}次重试: {}",
+                            relative,
+                            attempt + 1,
+                            e.message
+                        );
+                        sleep(DOWNLOAD_RETRY_DELAY * (attempt + 1)).await;
+                    }
+                    Err(e) => return Err(e),
+                }
+                attempt += 1;
+            }
+            reporter.progress.copied_files += 1;
+            reporter.report(false);
+        }
+        Ok(())
+    }
+
+    /// 从已经写入的written字节处继续下载一个文件，服务端不支持Range时跳过已经写入的部分
+    async fn stream_folder_file<F: FnMut(&TransferProgress)>(
+        server_url: &str,
+        source: &str,
+        written: &mut u64,
+        sink: &mut FolderSink,
+        token: &CancelToken,
+        reporter: &mut ProgressReporter<F>,
+    ) -> Result<(), ApiError> {
+        let endpoint = format!("files/download?path={}", encode_query(source));
+        let mut response = api_get_stream(server_url, &endpoint, *written).await?;
+        let mut skip = if response.status() == StatusCode::PARTIAL_CONTENT {
+            0
+        } else {
+            *written
+        };
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|e| ApiError::network(format!("读取文件内容失败: {}", e)))?
+        {
+            if token.is_cancelled() {
+                return Err(ApiError::new(499, "下载已取消".to_string()));
+            }
+            let skipped = skip.min(chunk.len() as u64);
+            skip -= skipped;
+            let data = &chunk[skipped as usize..];
+            sink.write(data)?;
+            *written += data.len() as u64;
+            reporter.progress.copied_bytes += data.len() as u64;
+            reporter.report(false);
+        }
         Ok(())
     }
+
+    /// 网络错误和服务端错误可以重试，4xx说明请求本身有问题
+    fn is_retryable(error: &ApiError) -> bool {
+        error.status_code == 0 || error.status_code >= 500
+    }
+
+    /// 同步遍历本地目录，得到按先目录后内容排列的上传清单，符号链接会被跳过
+    fn plan_upload(local: &Path, remote: &str) -> Vec<UploadItem> {
+        let Ok(metadata) = local.symlink_metadata() else {
+            return Vec::new();
+        };
+        if metadata.is_file() {
+            return vec![UploadItem::File {
+                local: local.to_path_buf(),
+                remote: remote.to_string(),
+                size: metadata.len(),
+            }];
+        }
+        if !metadata.is_dir() {
+            return Vec::new();
+        }
+
+        let mut items = vec![UploadItem::Directory(remote.to_string())];
+        if let Ok(entries) = local.read_dir() {
+            for entry in entries.flatten() {
+                let name = entry.file_name().to_string_lossy().into_owned();
+                items.extend(Self::plan_upload(
+                    &entry.path(),
+                    &join_remote(remote, &name),
+                ));
+            }
+        }
+        items
+    }
+
+    /// 上传单个文件，返回None表示服务端按冲突策略跳过了该文件
+    async fn upload_file(
+        local: &Path,
+        remote: &str,
+        size: u64,
+        conflict: ConflictPolicy,
+        token: &CancelToken,
+        progress: &mut TransferProgress,
+        on_progress: &mut (impl FnMut(&TransferProgress) + Send),
+    ) -> Result<Option<String>, ApiError> {
+        let server_url = Self::get_server_url();
+        let init = UploadInitRequest {
+            path: remote.to_string(),
+            size,
+            conflict,
+        };
+        let session: UploadSession = api_post(&server_url, "files/upload", &init).await?;
+        if session.skipped {
+            progress.copied_bytes += size;
+            return Ok(None);
+        }
+
+        progress.current_file = local
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+
+        let sent = Self::send_chunks(local, &session, token, progress, on_progress).await;
+        if let Err(e) = sent {
+            // 失败或取消时通知服务端丢弃已接收的分块
+            let endpoint = format!("files/upload/{}", session.upload_id);
+            let _ = api_delete_success(&server_url, &endpoint).await;
+            return Err(e);
+        }
+
+        let endpoint = format!("files/upload/{}/complete", session.upload_id);
+        let file: FileInfo = api_post(&server_url, &endpoint, &()).await?;
+        Ok(Some(file.path))
+    }
+
+    async fn send_chunks(
+        local: &Path,
+        session: &UploadSession,
+        token: &CancelToken,
+        progress: &mut TransferProgress,
+        on_progress: &mut (impl FnMut(&TransferProgress) + Send),
+    ) -> Result<(), ApiError> {
+        let server_url = Self::get_server_url();
+        let mut file = File::open(local)
+            .await
+            .map_err(|e| ApiError::new(500, format!("打开文件失败: {}", e)))?;
+
+        let mut offset = 0u64;
+        let mut buf = vec![0u8; UPLOAD_CHUNK_SIZE];
+        loop {
+            if token.is_cancelled() {
+                return Err(ApiError::new(499, "上传已取消".to_string()));
+            }
+
+            let read = read_chunk(&mut file, &mut buf).await?;
+            if read == 0 {
+                break;
+            }
+
+            let endpoint = format!("files/upload/{}?offset={}", session.upload_id, offset);
+            api_post_bytes::<u64>(&server_url, &endpoint, buf[..read].to_vec()).await?;
+            offset += read as u64;
+            progress.copied_bytes += read as u64;
+            on_progress(progress);
+        }
+        Ok(())
+    }
+}
+
+enum UploadItem {
+    Directory(String),
+    File {
+        local: PathBuf,
+        remote: String,
+        size: u64,
+    },
+}
+
+fn join_remote(dir: &str, name: &str) -> String {
+    format!("{}/{}", dir.trim_end_matches('/'), name)
+}
+
+/// 尽量读满一个分块，减少请求次数
+async fn read_chunk(file: &mut File, buf: &mut [u8]) -> Result<usize, ApiError> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        let read = file
+            .read(&mut buf[filled..])
+            .await
+            .map_err(|e| ApiError::new(500, format!("读取文件失败: {}", e)))?;
+        if read == 0 {
+            break;
+        }
+        filled += read;
+    }
+    Ok(filled)
 }
@@ -51,14 +51,11 @@ impl TranscodeRepo for OnlineTranscodeRepo {
         let app_clone = app.clone();
         let handle = tokio::spawn(async move {
             while POLLING_ACTIVE.load(Ordering::Relaxed) {
-                match Self::get(id.clone()).await {
-                    Ok(res) => {
-                        Self::emit_pooling_status(&res, &app_clone).await;
-                        if res.progress >= 0.99 || res.error.is_some() {
-                            break;
-                        }
+                if let Ok(res) = Self::get(id.clone()).await {
+                    Self::emit_pooling_status(&res, &app_clone).await;
+                    if res.progress >= 0.99 || res.error.is_some() {
+                        break;
                     }
-                    Err(_) => {}
                 }
 
                 sleep(Duration::from_secs(2)).await;
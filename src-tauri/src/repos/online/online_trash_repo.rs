@@ -0,0 +1,67 @@
+use crate::{
+    models::{
+        error::ApiError,
+        files::{ConflictPolicy, DeleteSummary, TransferResult},
+        trash::{RestoreTrashRequest, TrashItemDto},
+    },
+    repos::{online::OnlineRepo, trash_repo::TrashRepo, Repo},
+    services::api_service::{api_delete, api_get, api_post, encode_query},
+};
+
+pub struct OnlineTrashRepo;
+
+impl Repo for OnlineTrashRepo {
+    type Id = i64;
+    type Item = TrashItemDto;
+    type CreateRequest = ();
+    type UpdateRequest = ();
+
+    async fn get_all() -> Result<Vec<Self::Item>, ApiError> {
+        api_get(&Self::get_server_url(), "trash").await
+    }
+}
+
+impl OnlineRepo for OnlineTrashRepo {}
+
+impl TrashRepo for OnlineTrashRepo {
+    async fn move_to_trash(path: &str, recursive: bool) -> Result<TrashItemDto, ApiError> {
+        let endpoint = format!("trash?path={}&recursive={}", encode_query(path), recursive);
+        api_post(&Self::get_server_url(), &endpoint, &()).await
+    }
+
+    async fn restore(ids: &[i64], conflict: ConflictPolicy) -> Result<TransferResult, ApiError> {
+        let request = RestoreTrashRequest {
+            ids: ids.to_vec(),
+            conflict,
+        };
+        api_post(&Self::get_server_url(), "trash/restore", &request).await
+    }
+
+    async fn empty() -> Result<DeleteSummary, ApiError> {
+        api_delete(&Self::get_server_url(), "trash").await
+    }
+
+    /// 服务端自行负责过期清理
+    async fn purge_expired(_max_age_days: u32) -> Result<DeleteSummary, ApiError> {
+        Ok(DeleteSummary::default())
+    }
+}
+
+impl OnlineTrashRepo {
+    /// 旧版本的服务端没有回收站接口，只有405和501算作不支持，404可能只是文件不存在
+    fn is_unsupported(error: &ApiError) -> bool {
+        matches!(error.status_code, 405 | 501)
+    }
+
+    /// 服务端不支持回收站时不能悄悄改为永久删除，返回501让前端询问用户是否用permanent重试
+    pub fn unsupported_hint(error: ApiError) -> ApiError {
+        if Self::is_unsupported(&error) {
+            ApiError::new(
+                501,
+                "服务器没有回收站，删除后无法恢复；确认后请以永久删除重试".to_string(),
+            )
+        } else {
+            error
+        }
+    }
+}
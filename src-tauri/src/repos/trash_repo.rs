@@ -0,0 +1,19 @@
+use crate::{
+    models::{
+        error::ApiError,
+        files::{ConflictPolicy, DeleteSummary, TransferResult},
+        trash::TrashItemDto,
+    },
+    repos::Repo,
+};
+
+pub trait TrashRepo: Repo {
+    /// 把文件或目录移入回收站，非空目录需要recursive为true
+    async fn move_to_trash(path: &str, recursive: bool) -> Result<TrashItemDto, ApiError>;
+    /// 把回收站条目还原到原来的位置，原位置的父目录不存在时会被重新创建
+    async fn restore(ids: &[i64], conflict: ConflictPolicy) -> Result<TransferResult, ApiError>;
+    /// 永久删除回收站中的全部条目
+    async fn empty() -> Result<DeleteSummary, ApiError>;
+    /// 永久删除移入回收站超过指定天数的条目
+    async fn purge_expired(max_age_days: u32) -> Result<DeleteSummary, ApiError>;
+}
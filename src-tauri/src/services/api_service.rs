@@ -1,14 +1,19 @@
 use crate::models::api_response::ApiResponse;
 use crate::models::error::ApiError;
-use reqwest::{Client, Method, Response};
+use crate::services::auth::AuthManager;
+use reqwest::{
+    header::{CONTENT_RANGE, CONTENT_TYPE, RANGE},
+    Client, Method, RequestBuilder, Response, StatusCode,
+};
 use serde::{Deserialize, Serialize};
 use std::sync::OnceLock;
+use std::time::Duration;
 
 // 全局 HTTP 客户端，只创建一次
 static HTTP_CLIENT: OnceLock<Client> = OnceLock::new();
 
 fn get_client() -> &'static Client {
-    HTTP_CLIENT.get_or_init(|| Client::new())
+    HTTP_CLIENT.get_or_init(Client::new)
 }
 
 /// 构建完整的 URL
@@ -20,6 +25,11 @@ fn build_url(server_url: &str, endpoint: &str) -> String {
     }
 }
 
+/// 编码查询参数的值，路径里的空格、&、#、+等字符不会截断或篡改查询串
+pub fn encode_query(value: &str) -> String {
+    form_urlencoded::byte_serialize(value.as_bytes()).collect()
+}
+
 /// 检查响应状态码并处理错误
 async fn handle_response_error(response: Response) -> Result<Response, ApiError> {
     if !response.status().is_success() {
@@ -47,27 +57,60 @@ async fn handle_response_error(response: Response) -> Result<Response, ApiError>
     Ok(response)
 }
 
+/// 有登录令牌时附带 Bearer 请求头
+fn authorize(request: RequestBuilder, token: Option<&str>) -> RequestBuilder {
+    match token {
+        Some(token) => request.bearer_auth(token),
+        None => request,
+    }
+}
+
+/// 附带登录令牌发送请求，401 时刷新令牌后重试一次，仍然是 401 则通知前端重新登录；
+/// 刷新时服务器不可达则返回刷新的错误
+///
+/// build 每次调用都要构造一个新的请求，返回的响应没有检查状态码
+async fn send_authorized(
+    server_url: &str,
+    build: impl Fn() -> RequestBuilder,
+) -> Result<Response, ApiError> {
+    let build = &build;
+    let send = |token: Option<String>| async move {
+        authorize(build(), token.as_deref())
+            .send()
+            .await
+            .map_err(|e| ApiError::network(format!("发送请求失败: {}", e)))
+    };
+
+    let token = AuthManager::access_token(server_url);
+    let mut response = send(token.clone()).await?;
+    if response.status() != StatusCode::UNAUTHORIZED {
+        return Ok(response);
+    }
+    if AuthManager::refresh(server_url, token.as_deref()).await? {
+        response = send(AuthManager::access_token(server_url)).await?;
+        if response.status() == StatusCode::UNAUTHORIZED {
+            AuthManager::require_login(server_url);
+        }
+    }
+    Ok(response)
+}
+
 /// 发送请求并获取原始响应
 async fn send_request(
     method: Method,
     server_url: &str,
     endpoint: &str,
 ) -> Result<Response, ApiError> {
+    if !matches!(
+        method,
+        Method::GET | Method::POST | Method::DELETE | Method::PUT
+    ) {
+        return Err(ApiError::network("不支持的 HTTP 方法".to_string()));
+    }
     let url = build_url(server_url, endpoint);
-    let client = get_client();
-
-    let request = match method {
-        Method::GET => client.get(&url),
-        Method::POST => client.post(&url),
-        Method::DELETE => client.delete(&url),
-        Method::PUT => client.put(&url),
-        _ => return Err(ApiError::network("不支持的 HTTP 方法".to_string())),
-    };
 
-    let response = request
-        .send()
-        .await
-        .map_err(|e| ApiError::network(format!("发送请求失败: {}", e)))?;
+    let response =
+        send_authorized(server_url, || get_client().request(method.clone(), &url)).await?;
 
     handle_response_error(response).await
 }
@@ -79,19 +122,15 @@ async fn send_request_with_json<T: Serialize>(
     endpoint: &str,
     json_data: &T,
 ) -> Result<Response, ApiError> {
+    if !matches!(method, Method::POST | Method::PUT) {
+        return Err(ApiError::network("该方法不支持 JSON 数据".to_string()));
+    }
     let url = build_url(server_url, endpoint);
-    let client = get_client();
 
-    let request = match method {
-        Method::POST => client.post(&url).json(json_data),
-        Method::PUT => client.put(&url).json(json_data),
-        _ => return Err(ApiError::network("该方法不支持 JSON 数据".to_string())),
-    };
-
-    let response = request
-        .send()
-        .await
-        .map_err(|e| ApiError::network(format!("发送请求失败: {}", e)))?;
+    let response = send_authorized(server_url, || {
+        get_client().request(method.clone(), &url).json(json_data)
+    })
+    .await?;
 
     handle_response_error(response).await
 }
@@ -129,6 +168,26 @@ pub async fn api_post<T: for<'de> Deserialize<'de>, R: Serialize>(
     parse_api_response(response).await
 }
 
+/// POST 原始字节数据并解析为 ApiResponse<T>，用于分块上传
+pub async fn api_post_bytes<T: for<'de> Deserialize<'de>>(
+    server_url: &str,
+    endpoint: &str,
+    bytes: Vec<u8>,
+) -> Result<T, ApiError> {
+    let url = build_url(server_url, endpoint);
+    // 可能需要重试，每次请求都复制一份数据
+    let response = send_authorized(server_url, || {
+        get_client()
+            .post(&url)
+            .header(CONTENT_TYPE, "application/octet-stream")
+            .body(bytes.clone())
+    })
+    .await?;
+
+    let response = handle_response_error(response).await?;
+    parse_api_response(response).await
+}
+
 pub async fn api_put<T: for<'de> Deserialize<'de>, R: Serialize>(
     server_url: &str,
     endpoint: &str,
@@ -148,13 +207,104 @@ pub async fn api_post_success<R: Serialize>(
     Ok(response.status().is_success())
 }
 
+/// DELETE 请求并解析为 ApiResponse<T>
+pub async fn api_delete<T: for<'de> Deserialize<'de>>(
+    server_url: &str,
+    endpoint: &str,
+) -> Result<T, ApiError> {
+    let response = send_request(Method::DELETE, server_url, endpoint).await?;
+    parse_api_response(response).await
+}
+
 /// DELETE 请求并返回是否成功
 pub async fn api_delete_success(server_url: &str, endpoint: &str) -> Result<bool, ApiError> {
     let response = send_request(Method::DELETE, server_url, endpoint).await?;
     Ok(response.status().is_success())
 }
 
-/// GET 请求并返回字节数据（用于文件下载）
+/// 附带Range请求头发送GET请求，range为空时请求完整内容，返回的响应没有检查状态码
+async fn send_range_request(
+    server_url: &str,
+    endpoint: &str,
+    range: Option<String>,
+) -> Result<Response, ApiError> {
+    let url = build_url(server_url, endpoint);
+    send_authorized(server_url, || {
+        let request = get_client().get(&url);
+        match &range {
+            Some(range) => request.header(RANGE, range),
+            None => request,
+        }
+    })
+    .await
+}
+
+/// GET 请求并返回原始响应，用于流式读取大文件；offset大于0时通过Range请求头从断点继续
+pub async fn api_get_stream(
+    server_url: &str,
+    endpoint: &str,
+    offset: u64,
+) -> Result<Response, ApiError> {
+    let range = (offset > 0).then(|| format!("bytes={}-", offset));
+    let response = send_range_request(server_url, endpoint, range).await?;
+
+    handle_response_error(response).await
+}
+
+/// 读取[offset, offset + length)范围内的字节，同时返回文件总大小（服务端未提供时为空）
+pub async fn api_get_range(
+    server_url: &str,
+    endpoint: &str,
+    offset: u64,
+    length: u64,
+) -> Result<(Vec<u8>, Option<u64>), ApiError> {
+    let range = format!("bytes={}-{}", offset, offset + length.max(1) - 1);
+    let response = send_range_request(server_url, endpoint, Some(range)).await?;
+
+    // Content-Range形如`bytes 0-99/1234`或`bytes */1234`
+    let content_range_total = |response: &Response| {
+        response
+            .headers()
+            .get(CONTENT_RANGE)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.rsplit('/').next())
+            .and_then(|v| v.parse::<u64>().ok())
+    };
+
+    // 416说明offset已经超出文件末尾
+    if response.status() == StatusCode::RANGE_NOT_SATISFIABLE {
+        return Ok((Vec::new(), content_range_total(&response)));
+    }
+    let mut response = handle_response_error(response).await?;
+
+    // 服务端不支持Range时返回完整内容，需要自己跳过offset之前的部分
+    let partial = response.status() == StatusCode::PARTIAL_CONTENT;
+    let total = if partial {
+        content_range_total(&response)
+    } else {
+        response.content_length()
+    };
+    let mut skip = if partial { 0 } else { offset as usize };
+    let length = length as usize;
+
+    let mut data = Vec::new();
+    while data.len() < length {
+        let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|e| ApiError::network(format!("读取文件内容失败: {}", e)))?
+        else {
+            break;
+        };
+        let start = skip.min(chunk.len());
+        skip -= start;
+        data.extend_from_slice(&chunk[start..]);
+    }
+    data.truncate(length);
+    Ok((data, total))
+}
+
+/// GET 请求并返回字节数据（用于缩略图等小文件）
 pub async fn api_get_bytes(server_url: &str, endpoint: &str) -> Result<Vec<u8>, ApiError> {
     let response = send_request(Method::GET, server_url, endpoint).await?;
 
@@ -165,3 +315,35 @@ pub async fn api_get_bytes(server_url: &str, endpoint: &str) -> Result<Vec<u8>,
 
     Ok(bytes.to_vec())
 }
+
+/// 请求服务端的健康检查接口，只要收到响应就返回状态码
+pub async fn api_health(server_url: &str, timeout: Duration) -> Result<StatusCode, ApiError> {
+    let response = get_client()
+        .get(build_url(server_url, "health"))
+        .timeout(timeout)
+        .send()
+        .await
+        .map_err(|e| ApiError::network(format!("无法连接服务器: {}", e)))?;
+    Ok(response.status())
+}
+
+/// 不经过自动刷新令牌的 POST 请求，用于登录、刷新和注销接口本身
+pub async fn api_post_with_token<T: for<'de> Deserialize<'de>, R: Serialize>(
+    server_url: &str,
+    endpoint: &str,
+    json_data: &R,
+    token: Option<&str>,
+) -> Result<T, ApiError> {
+    let response = authorize(
+        get_client()
+            .post(build_url(server_url, endpoint))
+            .json(json_data),
+        token,
+    )
+    .send()
+    .await
+    .map_err(|e| ApiError::network(format!("发送请求失败: {}", e)))?;
+
+    let response = handle_response_error(response).await?;
+    parse_api_response(response).await
+}
@@ -0,0 +1,212 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Mutex as StdMutex, OnceLock},
+};
+
+use once_cell::sync::Lazy;
+use tauri::{AppHandle, Emitter};
+use tokio::sync::Mutex;
+
+use crate::{
+    commands::config::with_config,
+    models::{
+        auth::{AuthRequiredPayload, AuthStatus, AuthTokens, LoginRequest, StoredAuth},
+        error::ApiError,
+        profile::ProfileKind,
+    },
+    services::{api_service::api_post_with_token, private_store::PrivateStore},
+};
+
+const AUTH_FILE: &str = "auth.json";
+
+static APP_HANDLE: OnceLock<AppHandle> = OnceLock::new();
+
+// 各服务器的登录状态，键为服务器地址
+static SESSIONS: Lazy<StdMutex<HashMap<String, StoredAuth>>> =
+    Lazy::new(|| StdMutex::new(HashMap::new()));
+
+// 已经通知过前端需要重新登录的服务器，重新登录前不再重复通知
+static LOGIN_REQUIRED: Lazy<StdMutex<HashSet<String>>> =
+    Lazy::new(|| StdMutex::new(HashSet::new()));
+
+// 同一时间只刷新一次，并发请求同时收到401时后来的直接使用新令牌
+static REFRESHING: Mutex<()> = Mutex::const_new(());
+
+/// 在线模式的登录状态，令牌按服务器地址保存在应用数据目录下只有当前用户可读的文件中
+pub struct AuthManager;
+
+impl AuthManager {
+    /// 启动时读取保存的令牌
+    pub fn init(app: &AppHandle) -> Result<(), ApiError> {
+        let _ = APP_HANDLE.set(app.clone());
+        if let Some(sessions) = PrivateStore::load(AUTH_FILE)? {
+            *SESSIONS.lock().unwrap() = sessions;
+        }
+        Ok(())
+    }
+
+    pub fn access_token(server_url: &str) -> Option<String> {
+        SESSIONS
+            .lock()
+            .unwrap()
+            .get(server_url)
+            .map(|session| session.access_token.clone())
+    }
+
+    pub fn status(server_url: &str) -> AuthStatus {
+        let username = SESSIONS
+            .lock()
+            .unwrap()
+            .get(server_url)
+            .map(|session| session.username.clone());
+        AuthStatus {
+            server_url: server_url.to_string(),
+            logged_in: username.is_some(),
+            username,
+        }
+    }
+
+    pub async fn login(server_url: &str, request: &LoginRequest) -> Result<AuthStatus, ApiError> {
+        let tokens: AuthTokens =
+            api_post_with_token(server_url, "auth/login", request, None).await?;
+        Self::store(
+            server_url,
+            Some(StoredAuth {
+                username: request.username.clone(),
+                access_token: tokens.access_token,
+                refresh_token: tokens.refresh_token,
+            }),
+        )?;
+        Ok(Self::status(server_url))
+    }
+
+    /// 通知服务端作废令牌，服务端不可达时也会删除本地保存的令牌
+    pub async fn logout(server_url: &str) -> Result<(), ApiError> {
+        if let Some(token) = Self::access_token(server_url) {
+            let result = api_post_with_token::<serde_json::Value, _>(
+                server_url,
+                "auth/logout",
+                &serde_json::json!({}),
+                Some(&token),
+            )
+            .await;
+            if let Err(e) = result {
+                eprintln!("注销登录失败: {}", e.message);
+            }
+        }
+        Self::store(server_url, None)
+    }
+
+    /// 收到401后调用，返回是否拿到了可以重试的新令牌
+    ///
+    /// 先用refresh token刷新，被拒绝时再用激活的连接配置中保存的账号重新登录，
+    /// 都被拒绝才清除登录状态并通知前端；网络错误等其他失败保留令牌并返回错误。
+    pub async fn refresh(server_url: &str, failed_token: Option<&str>) -> Result<bool, ApiError> {
+        let _guard = REFRESHING.lock().await;
+        let current = SESSIONS.lock().unwrap().get(server_url).cloned();
+        // 等待期间其他请求已经换了令牌，或者已经判定需要重新登录
+        if current.as_ref().map(|c| c.access_token.as_str()) != failed_token {
+            return Ok(current.is_some());
+        }
+
+        if let Some(current) = current {
+            if let Some(refresh_token) = current.refresh_token.clone() {
+                let result: Result<AuthTokens, ApiError> = api_post_with_token(
+                    server_url,
+                    "auth/refresh",
+                    &serde_json::json!({ "refreshToken": refresh_token }),
+                    None,
+                )
+                .await;
+                match result {
+                    Ok(tokens) => {
+                        let session = StoredAuth {
+                            username: current.username,
+                            access_token: tokens.access_token,
+                            refresh_token: tokens.refresh_token.or(Some(refresh_token)),
+                        };
+                        Self::store(server_url, Some(session))?;
+                        return Ok(true);
+                    }
+                    Err(e) if Self::is_rejected(&e) => {
+                        eprintln!("刷新登录令牌被拒绝: {}", e.message)
+                    }
+                    Err(e) => return Err(e),
+                }
+            }
+        }
+
+        if let Some(request) = Self::profile_credentials(server_url) {
+            match Self::login(server_url, &request).await {
+                Ok(_) => return Ok(true),
+                Err(e) if Self::is_rejected(&e) => {
+                    eprintln!("连接配置中的账号登录被拒绝: {}", e.message)
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        if let Err(e) = Self::store(server_url, None) {
+            eprintln!("{}", e.message);
+        }
+        Self::require_login(server_url);
+        Ok(false)
+    }
+
+    /// 通知前端需要重新登录，重新登录之前只通知一次
+    pub fn require_login(server_url: &str) {
+        if !LOGIN_REQUIRED
+            .lock()
+            .unwrap()
+            .insert(server_url.to_string())
+        {
+            return;
+        }
+        if let Some(app) = APP_HANDLE.get() {
+            let _ = app.emit(
+                "auth-required",
+                AuthRequiredPayload {
+                    server_url: server_url.to_string(),
+                },
+            );
+        }
+    }
+
+    /// 服务端明确拒绝了令牌或账号，而不是暂时无法处理
+    fn is_rejected(error: &ApiError) -> bool {
+        matches!(error.status_code, 400 | 401)
+    }
+
+    /// 激活的连接配置指向这个服务器并且保存了账号和密码时返回登录请求
+    fn profile_credentials(server_url: &str) -> Option<LoginRequest> {
+        let (profile_id, credentials) = with_config(|config| {
+            let active_id = config.active_profile_id.as_deref()?;
+            config
+                .profiles
+                .iter()
+                .find(|p| p.id == active_id)
+                .filter(|p| p.kind == ProfileKind::Server && p.location == server_url)
+                .and_then(|p| Some((p.id.clone(), p.credentials.clone()?)))
+        })?;
+        if !credentials.has_password {
+            return None;
+        }
+        Some(LoginRequest {
+            username: credentials.username,
+            password: PrivateStore::profile_password(&profile_id)?,
+        })
+    }
+
+    fn store(server_url: &str, session: Option<StoredAuth>) -> Result<(), ApiError> {
+        let mut sessions = SESSIONS.lock().unwrap();
+        match session {
+            Some(session) => {
+                LOGIN_REQUIRED.lock().unwrap().remove(server_url);
+                sessions.insert(server_url.to_string(), session)
+            }
+            None => sessions.remove(server_url),
+        };
+        let sessions = sessions.clone();
+        PrivateStore::save(AUTH_FILE, &sessions)
+    }
+}
@@ -0,0 +1,111 @@
+use std::{path::Path, time::Duration};
+
+use reqwest::{StatusCode, Url};
+
+use crate::{
+    commands::config::AppConfig,
+    models::config_check::{CheckLevel, ConfigCheckResult, FieldCheck},
+    services::api_service::api_health,
+};
+
+const HEALTH_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// 检查配置能否正常使用，当前模式用不到的连接只给出警告
+pub struct ConfigValidator;
+
+impl ConfigValidator {
+    pub async fn validate(config: &AppConfig) -> ConfigCheckResult {
+        let (server, ffmpeg, ffprobe) = tokio::join!(
+            Self::check_server(&config.server_url, config.online),
+            Self::check_tool("ffmpeg"),
+            Self::check_tool("ffprobe"),
+        );
+        let base_dir = Self::check_base_dir(&config.base_dir, !config.online);
+        ConfigCheckResult::new(vec![base_dir, server, ffmpeg, ffprobe])
+    }
+
+    fn check_base_dir(base_dir: &str, required: bool) -> FieldCheck {
+        let failure = if required {
+            CheckLevel::Error
+        } else {
+            CheckLevel::Warning
+        };
+        let path = Path::new(base_dir);
+        if base_dir.trim().is_empty() {
+            return FieldCheck::new("baseDir", failure, "目录不能为空");
+        }
+        if !path.exists() {
+            return FieldCheck::new("baseDir", failure, "目录不存在");
+        }
+        if !path.is_dir() {
+            return FieldCheck::new("baseDir", failure, "不是目录");
+        }
+        match path.read_dir() {
+            Ok(_) => FieldCheck::new("baseDir", CheckLevel::Ok, "目录可以读取"),
+            Err(e) => FieldCheck::new("baseDir", failure, format!("无法读取目录: {}", e)),
+        }
+    }
+
+    async fn check_server(server_url: &str, required: bool) -> FieldCheck {
+        let failure = if required {
+            CheckLevel::Error
+        } else {
+            CheckLevel::Warning
+        };
+        match Url::parse(server_url) {
+            Ok(url) if matches!(url.scheme(), "http" | "https") => {}
+            Ok(_) => return FieldCheck::new("serverUrl", failure, "只支持http和https地址"),
+            Err(e) => return FieldCheck::new("serverUrl", failure, format!("地址无效: {}", e)),
+        }
+
+        match api_health(server_url, HEALTH_TIMEOUT).await {
+            Ok(status) if status.is_success() => {
+                FieldCheck::new("serverUrl", CheckLevel::Ok, "服务器连接正常")
+            }
+            // 旧版本的服务端没有健康检查接口，能收到响应说明地址是对的
+            Ok(StatusCode::NOT_FOUND) => FieldCheck::new(
+                "serverUrl",
+                CheckLevel::Warning,
+                "服务器可以访问，但没有提供健康检查接口",
+            ),
+            // 健康检查接口也要求登录的服务端，地址和网络都没有问题
+            Ok(StatusCode::UNAUTHORIZED | StatusCode::FORBIDDEN) => {
+                FieldCheck::new("serverUrl", CheckLevel::Warning, "服务器可以访问，需要登录")
+            }
+            Ok(status) => FieldCheck::new(
+                "serverUrl",
+                failure,
+                format!("服务器状态异常，状态码: {}", status.as_u16()),
+            ),
+            Err(e) => FieldCheck::new("serverUrl", failure, e.message),
+        }
+    }
+
+    /// 缺少ffmpeg或ffprobe时视频缩略图、转码和媒体信息不可用，但不影响浏览文件
+    async fn check_tool(name: &str) -> FieldCheck {
+        match tokio::process::Command::new(name)
+            .arg("-version")
+            .output()
+            .await
+        {
+            Ok(output) if output.status.success() => {
+                let version = String::from_utf8_lossy(&output.stdout)
+                    .lines()
+                    .next()
+                    .unwrap_or_default()
+                    .to_string();
+                FieldCheck::new(name, CheckLevel::Ok, version)
+            }
+            Ok(output) => FieldCheck::new(
+                name,
+                CheckLevel::Warning,
+                format!("{}运行异常，状态码: {:?}", name, output.status.code()),
+            ),
+            Err(_) => FieldCheck::new(
+                name,
+                CheckLevel::Warning,
+                format!("没有找到{}，视频缩略图和转码等功能不可用", name),
+            ),
+        }
+    }
+}
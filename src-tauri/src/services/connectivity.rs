@@ -0,0 +1,88 @@
+use std::{sync::Mutex, time::Duration};
+
+use tauri::{AppHandle, Emitter};
+
+use crate::{
+    commands::config::{is_online, with_config},
+    models::connectivity::ConnectivityStatus,
+    services::{api_service::api_health, download_queue::DownloadQueue},
+};
+
+const CHECK_INTERVAL: Duration = Duration::from_secs(10);
+const CHECK_TIMEOUT: Duration = Duration::from_secs(3);
+
+// 最近一次检查的服务器地址及其是否可达，切换服务器后旧的结果不再适用
+static LAST_STATE: Mutex<Option<(String, bool)>> = Mutex::new(None);
+
+/// 定期检查服务器是否可达，状态变化时推送`connectivity-changed`事件
+pub struct ConnectivityMonitor;
+
+impl ConnectivityMonitor {
+    pub async fn start(app: AppHandle) {
+        loop {
+            Self::check(&app).await;
+            tokio::time::sleep(CHECK_INTERVAL).await;
+        }
+    }
+
+    /// 立即检查一次，离线模式下不发请求
+    pub async fn check(app: &AppHandle) -> ConnectivityStatus {
+        if !is_online() {
+            return Self::status();
+        }
+        let server_url = with_config(|config| config.server_url.clone());
+        // 只要收到响应就说明服务器在线，是否提供健康检查接口无关紧要
+        let reachable = api_health(&server_url, CHECK_TIMEOUT).await.is_ok();
+
+        let changed = {
+            let mut last_state = LAST_STATE.lock().unwrap();
+            match last_state.replace((server_url.clone(), reachable)) {
+                Some((url, previous)) if url == server_url => previous != reachable,
+                // 初次检查或刚切换服务器时默认可达，只有不可达才算变化
+                _ => !reachable,
+            }
+        };
+        let status = Self::status();
+        if changed {
+            let _ = app.emit("connectivity-changed", status.clone());
+            if reachable {
+                // 服务器恢复后继续下载队列中等待的项
+                DownloadQueue::schedule();
+            }
+        }
+        status
+    }
+
+    pub fn status() -> ConnectivityStatus {
+        ConnectivityStatus {
+            server_url: with_config(|config| config.server_url.clone()),
+            reachable: Self::is_reachable(),
+            using_mirror: Self::using_mirror(),
+        }
+    }
+
+    /// 还没有检查过当前服务器时按可达处理
+    pub fn is_reachable() -> bool {
+        if !is_online() {
+            return true;
+        }
+        let server_url = with_config(|config| config.server_url.clone());
+        match LAST_STATE.lock().unwrap().as_ref() {
+            Some((url, reachable)) if *url == server_url => *reachable,
+            _ => true,
+        }
+    }
+
+    /// 开启了自动降级、配置了离线镜像且服务器不可达
+    pub fn using_mirror() -> bool {
+        let fallback = with_config(|config| {
+            config.online
+                && config.auto_fallback
+                && config
+                    .offline_mirror_dir
+                    .as_deref()
+                    .is_some_and(|dir| !dir.is_empty())
+        });
+        fallback && !Self::is_reachable()
+    }
+}
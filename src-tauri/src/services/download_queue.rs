@@ -0,0 +1,290 @@
+use std::{
+    collections::HashMap,
+    sync::{Mutex as StdMutex, OnceLock},
+};
+
+use once_cell::sync::Lazy;
+use sqlx::types::chrono::Utc;
+use tauri::{async_runtime::JoinHandle, AppHandle, Emitter};
+use tokio::sync::Mutex;
+
+use crate::{
+    commands::config::{current_source, is_online, with_config},
+    models::{
+        download::{DownloadItem, DownloadItemDto, DownloadStatus},
+        error::ApiError,
+        files::DownloadProgress,
+    },
+    repos::{
+        files_repo::{download_part_path, FilesRepo},
+        offline::{offline_files_repo::OfflineFilesRepo, Database},
+        online::online_files_repo::OnlineFilesRepo,
+    },
+};
+
+static APP_HANDLE: OnceLock<AppHandle> = OnceLock::new();
+
+// 正在下载的任务，键为队列项id
+static RUNNING: Lazy<Mutex<HashMap<i64, JoinHandle<()>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+// 下载中任务的最新进度，只在内存中保存，结束或暂停时才写回数据库
+static LIVE_PROGRESS: Lazy<StdMutex<HashMap<i64, DownloadProgress>>> =
+    Lazy::new(|| StdMutex::new(HashMap::new()));
+
+/// 持久化在sqlite中的下载队列，按配置的并发数依次下载
+pub struct DownloadQueue;
+
+impl DownloadQueue {
+    /// 数据库初始化后调用，上次退出时没有完成的下载会重新排队
+    pub async fn start(app: &AppHandle) -> Result<(), ApiError> {
+        let _ = APP_HANDLE.set(app.clone());
+        sqlx::query(r"UPDATE downloads SET status = ? WHERE status = ?")
+            .bind(DownloadStatus::Queued.to_string())
+            .bind(DownloadStatus::Downloading.to_string())
+            .execute(Database::get_pool()?)
+            .await
+            .map_err(|e| ApiError::new(500, format!("恢复下载队列失败: {}", e)))?;
+        Self::schedule();
+        Ok(())
+    }
+
+    pub async fn enqueue(path: &str, filename: &str) -> Result<DownloadItemDto, ApiError> {
+        let (online, source) = (is_online(), current_source());
+        let now = Utc::now();
+        let result = sqlx::query(
+            r"
+            INSERT INTO downloads
+            (online, source, path, filename, status, created_at, updated_at)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
+            ",
+        )
+        .bind(online)
+        .bind(&source)
+        .bind(path)
+        .bind(filename)
+        .bind(DownloadStatus::Queued.to_string())
+        .bind(now)
+        .bind(now)
+        .execute(Database::get_pool()?)
+        .await
+        .map_err(|e| ApiError::new(500, format!("添加下载任务失败: {}", e)))?;
+
+        let item = DownloadItemDto::from(Self::get(result.last_insert_rowid()).await?);
+        Self::emit_status(&item);
+        Self::schedule();
+        Ok(item)
+    }
+
+    pub async fn list() -> Result<Vec<DownloadItemDto>, ApiError> {
+        let items =
+            sqlx::query_as::<_, DownloadItem>(r"SELECT * FROM downloads ORDER BY created_at DESC")
+                .fetch_all(Database::get_pool()?)
+                .await
+                .map_err(|e| ApiError::new(500, format!("获取下载列表失败: {}", e)))?;
+
+        let live_progress = LIVE_PROGRESS.lock().unwrap();
+        Ok(items
+            .into_iter()
+            .map(|item| {
+                let item = DownloadItemDto::from(item);
+                match live_progress.get(&item.id) {
+                    Some(progress) => Self::apply_progress(item, progress),
+                    None => item,
+                }
+            })
+            .collect())
+    }
+
+    /// 暂停时保留.part文件，恢复后从断点继续
+    pub async fn pause(id: i64) -> Result<DownloadItemDto, ApiError> {
+        let mut running = RUNNING.lock().await;
+        let status = DownloadStatus::from(Self::get(id).await?.status.as_str());
+        if status.is_finished() {
+            return Err(ApiError::new(409, "下载已经结束，无法暂停".to_string()));
+        }
+
+        if let Some(handle) = running.remove(&id) {
+            handle.abort();
+        }
+        let progress = LIVE_PROGRESS.lock().unwrap().remove(&id);
+        let item = Self::update_status(id, DownloadStatus::Paused, progress.as_ref(), None).await?;
+        drop(running);
+
+        Self::schedule();
+        Ok(item)
+    }
+
+    /// 暂停和失败的下载可以恢复，恢复后重新排队
+    pub async fn resume(id: i64) -> Result<DownloadItemDto, ApiError> {
+        let status = DownloadStatus::from(Self::get(id).await?.status.as_str());
+        if !matches!(status, DownloadStatus::Paused | DownloadStatus::Failed) {
+            return Err(ApiError::new(409, "只能恢复已暂停或失败的下载".to_string()));
+        }
+
+        let item = Self::update_status(id, DownloadStatus::Queued, None, None).await?;
+        Self::schedule();
+        Ok(item)
+    }
+
+    /// 取消后删除已下载的部分，已完成或已取消的下载不能再取消
+    pub async fn cancel(id: i64) -> Result<DownloadItemDto, ApiError> {
+        let mut running = RUNNING.lock().await;
+        let current = Self::get(id).await?;
+        match DownloadStatus::from(current.status.as_str()) {
+            DownloadStatus::Completed => {
+                return Err(ApiError::new(409, "下载已经完成，无法取消".to_string()));
+            }
+            DownloadStatus::Cancelled => {
+                return Err(ApiError::new(409, "下载已经取消".to_string()));
+            }
+            _ => {}
+        }
+
+        if let Some(handle) = running.remove(&id) {
+            handle.abort();
+        }
+        LIVE_PROGRESS.lock().unwrap().remove(&id);
+        if let Ok(part_path) = download_part_path(&current.source, &current.path, &current.filename)
+        {
+            let _ = tokio::fs::remove_file(part_path).await;
+        }
+        let item = Self::update_status(id, DownloadStatus::Cancelled, None, None).await?;
+        drop(running);
+
+        Self::schedule();
+        Ok(item)
+    }
+
+    /// 在后台补满空闲的下载槽位，可以在任意时刻重复调用
+    pub fn schedule() {
+        tauri::async_runtime::spawn(async {
+            if let Err(e) = Self::fill_slots().await {
+                eprintln!("调度下载队列失败: {}", e.message);
+            }
+        });
+    }
+
+    async fn fill_slots() -> Result<(), ApiError> {
+        // 队列还没有随数据库一起启动
+        if APP_HANDLE.get().is_none() {
+            return Ok(());
+        }
+
+        let mut running = RUNNING.lock().await;
+        let max_concurrent = with_config(|config| config.max_concurrent_downloads).max(1) as usize;
+        if running.len() >= max_concurrent {
+            return Ok(());
+        }
+
+        // 只下载属于当前服务器或base_dir的项，切换配置后其他项留在队列中
+        let (online, source) = (is_online(), current_source());
+        let items = sqlx::query_as::<_, DownloadItem>(
+            r"
+            SELECT * FROM downloads
+            WHERE status = ? AND online = ? AND source = ?
+            ORDER BY id LIMIT ?
+            ",
+        )
+        .bind(DownloadStatus::Queued.to_string())
+        .bind(online)
+        .bind(&source)
+        .bind((max_concurrent - running.len()) as i64)
+        .fetch_all(Database::get_pool()?)
+        .await
+        .map_err(|e| ApiError::new(500, format!("读取下载队列失败: {}", e)))?;
+
+        for item in items {
+            let id = item.id;
+            Self::update_status(id, DownloadStatus::Downloading, None, None).await?;
+            running.insert(id, tauri::async_runtime::spawn(Self::run(item)));
+        }
+        Ok(())
+    }
+
+    async fn run(item: DownloadItem) {
+        let id = item.id;
+        let mut base = DownloadItemDto::from(item.clone());
+        base.status = DownloadStatus::Downloading;
+        let on_progress = |progress: &DownloadProgress| {
+            LIVE_PROGRESS.lock().unwrap().insert(id, progress.clone());
+            Self::emit_status(&Self::apply_progress(base.clone(), progress));
+        };
+
+        let result = if item.online {
+            OnlineFilesRepo::download_file(&item.path, &item.filename, on_progress).await
+        } else {
+            OfflineFilesRepo::download_file(&item.path, &item.filename, on_progress).await
+        };
+
+        // 持有RUNNING直到状态写入，避免和暂停、取消交错
+        let mut running = RUNNING.lock().await;
+        running.remove(&id);
+        let progress = LIVE_PROGRESS.lock().unwrap().remove(&id);
+        let (status, error) = match result {
+            Ok(()) => (DownloadStatus::Completed, None),
+            Err(e) => (DownloadStatus::Failed, Some(e.message)),
+        };
+        if let Err(e) = Self::update_status(id, status, progress.as_ref(), error.as_deref()).await {
+            eprintln!("更新下载状态失败: {}", e.message);
+        }
+        drop(running);
+
+        Self::schedule();
+    }
+
+    /// 写入新状态并推送`download-status`事件，progress为空时保留原有的字节数
+    async fn update_status(
+        id: i64,
+        status: DownloadStatus,
+        progress: Option<&DownloadProgress>,
+        error: Option<&str>,
+    ) -> Result<DownloadItemDto, ApiError> {
+        sqlx::query(
+            r"
+            UPDATE downloads SET
+                status = ?,
+                downloaded_bytes = COALESCE(?, downloaded_bytes),
+                total_bytes = COALESCE(?, total_bytes),
+                error = ?,
+                updated_at = ?
+            WHERE id = ?
+            ",
+        )
+        .bind(status.to_string())
+        .bind(progress.map(|p| p.downloaded_bytes as i64))
+        .bind(progress.and_then(|p| p.total_bytes).map(|t| t as i64))
+        .bind(error)
+        .bind(Utc::now())
+        .bind(id)
+        .execute(Database::get_pool()?)
+        .await
+        .map_err(|e| ApiError::new(500, format!("更新下载状态失败: {}", e)))?;
+
+        let item = DownloadItemDto::from(Self::get(id).await?);
+        Self::emit_status(&item);
+        Ok(item)
+    }
+
+    async fn get(id: i64) -> Result<DownloadItem, ApiError> {
+        sqlx::query_as::<_, DownloadItem>(r"SELECT * FROM downloads WHERE id = ?")
+            .bind(id)
+            .fetch_optional(Database::get_pool()?)
+            .await
+            .map_err(|e| ApiError::new(500, format!("获取下载任务失败: {}", e)))?
+            .ok_or_else(|| ApiError::new(404, "下载任务不存在".to_string()))
+    }
+
+    fn apply_progress(mut item: DownloadItemDto, progress: &DownloadProgress) -> DownloadItemDto {
+        item.downloaded_bytes = progress.downloaded_bytes;
+        item.total_bytes = progress.total_bytes.or(item.total_bytes);
+        item.speed = progress.speed;
+        item
+    }
+
+    fn emit_status(item: &DownloadItemDto) {
+        if let Some(app) = APP_HANDLE.get() {
+            let _ = app.emit("download-status", item.clone());
+        }
+    }
+}
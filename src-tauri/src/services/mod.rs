@@ -1 +1,6 @@
 pub mod api_service;
+pub mod auth;
+pub mod config_validator;
+pub mod connectivity;
+pub mod download_queue;
+pub mod private_store;
@@ -0,0 +1,133 @@
+use std::{
+    collections::HashMap,
+    fs,
+    io::Write,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+use serde::{de::DeserializeOwned, Serialize};
+use tauri::{AppHandle, Manager};
+
+use crate::models::error::ApiError;
+
+// 连接配置的密码，键为连接配置id
+const PROFILE_SECRETS_FILE: &str = "profile-secrets.json";
+
+static APP_HANDLE: OnceLock<AppHandle> = OnceLock::new();
+
+/// 应用本地数据目录下只有当前用户可读写的文件，用于保存密码和令牌等不能放进设置文件的数据
+///
+/// - unix（Linux、macOS）：文件权限固定为0600，读取时发现权限被放宽会先收紧
+/// - Windows：数据目录位于%LOCALAPPDATA%下，继承用户目录只允许本人、SYSTEM和管理员访问的ACL
+/// - 移动端：数据目录位于应用沙盒内，其他应用无法访问
+///
+/// 文件本身不加密，能以当前用户身份读取文件的程序都能拿到其中的数据
+pub struct PrivateStore;
+
+impl PrivateStore {
+    pub fn init(app: &AppHandle) {
+        let _ = APP_HANDLE.set(app.clone());
+    }
+
+    /// 文件不存在时返回None
+    pub fn load<T: DeserializeOwned>(file: &str) -> Result<Option<T>, ApiError> {
+        let path = Self::file_path(file)?;
+        if !path.exists() {
+            return Ok(None);
+        }
+        Self::restrict_permissions(&path)?;
+        let content = fs::read_to_string(&path)
+            .map_err(|e| ApiError::new(500, format!("读取私密数据失败: {}", e)))?;
+        serde_json::from_str(&content)
+            .map(Some)
+            .map_err(|e| ApiError::new(500, format!("解析私密数据失败: {}", e)))
+    }
+
+    pub fn save<T: Serialize>(file: &str, value: &T) -> Result<(), ApiError> {
+        let content = serde_json::to_vec(value)
+            .map_err(|e| ApiError::new(500, format!("序列化私密数据失败: {}", e)))?;
+        Self::write_private(&Self::file_path(file)?, &content)
+    }
+
+    pub fn profile_password(profile_id: &str) -> Option<String> {
+        match Self::load::<HashMap<String, String>>(PROFILE_SECRETS_FILE) {
+            Ok(secrets) => secrets?.remove(profile_id),
+            Err(e) => {
+                eprintln!("{}", e.message);
+                None
+            }
+        }
+    }
+
+    /// password为None时删除保存的密码
+    pub fn set_profile_password(profile_id: &str, password: Option<&str>) -> Result<(), ApiError> {
+        let mut secrets: HashMap<String, String> =
+            Self::load(PROFILE_SECRETS_FILE)?.unwrap_or_default();
+        let changed = match password {
+            Some(password) => {
+                secrets.insert(profile_id.to_string(), password.to_string())
+                    != Some(password.to_string())
+            }
+            None => secrets.remove(profile_id).is_some(),
+        };
+        if !changed {
+            return Ok(());
+        }
+        Self::save(PROFILE_SECRETS_FILE, &secrets)
+    }
+
+    /// 先写临时文件再替换，替换前收紧临时文件的权限
+    fn write_private(path: &Path, content: &[u8]) -> Result<(), ApiError> {
+        let temp_path = path.with_extension("json.tmp");
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut file = options
+            .open(&temp_path)
+            .map_err(|e| ApiError::new(500, format!("保存私密数据失败: {}", e)))?;
+        // mode只在新建时生效，上次残留的临时文件可能是其他权限
+        Self::restrict_permissions(&temp_path)?;
+        file.write_all(content)
+            .and_then(|_| file.sync_all())
+            .map_err(|e| ApiError::new(500, format!("保存私密数据失败: {}", e)))?;
+        fs::rename(&temp_path, path)
+            .map_err(|e| ApiError::new(500, format!("保存私密数据失败: {}", e)))
+    }
+
+    #[cfg(unix)]
+    fn restrict_permissions(path: &Path) -> Result<(), ApiError> {
+        use std::os::unix::fs::PermissionsExt;
+
+        let metadata = fs::metadata(path)
+            .map_err(|e| ApiError::new(500, format!("读取私密数据的权限失败: {}", e)))?;
+        if metadata.permissions().mode() & 0o777 == 0o600 {
+            return Ok(());
+        }
+        fs::set_permissions(path, fs::Permissions::from_mode(0o600))
+            .map_err(|e| ApiError::new(500, format!("设置私密数据的权限失败: {}", e)))
+    }
+
+    /// 非unix平台依赖数据目录本身的访问控制，见PrivateStore的说明
+    #[cfg(not(unix))]
+    fn restrict_permissions(_path: &Path) -> Result<(), ApiError> {
+        Ok(())
+    }
+
+    fn file_path(file: &str) -> Result<PathBuf, ApiError> {
+        let app = APP_HANDLE
+            .get()
+            .ok_or_else(|| ApiError::new(500, "私密数据存储未初始化".to_string()))?;
+        let dir = app
+            .path()
+            .app_local_data_dir()
+            .map_err(|e| ApiError::new(500, format!("无法获取应用本地数据目录: {}", e)))?;
+        fs::create_dir_all(&dir)
+            .map_err(|e| ApiError::new(500, format!("创建数据目录失败: {}", e)))?;
+        Ok(dir.join(file))
+    }
+}
@@ -0,0 +1,31 @@
+use tokio::sync::mpsc;
+
+use crate::models::error::ApiError;
+
+/// 在阻塞线程中运行遍历目录、计算哈希、调用外部命令等耗时的同步操作，避免占住异步运行时
+pub async fn run<R: Send + 'static>(
+    work: impl FnOnce() -> R + Send + 'static,
+) -> Result<R, ApiError> {
+    tokio::task::spawn_blocking(work)
+        .await
+        .map_err(|e| ApiError::new(500, format!("后台任务失败: {}", e)))
+}
+
+/// 同run，work通过emit发出的事件（进度、逐个找到的结果等）在调用方的任务中交给on_event处理
+pub async fn run_with_events<E: Send + 'static, R: Send + 'static>(
+    work: impl FnOnce(&mut dyn FnMut(E)) -> R + Send + 'static,
+    mut on_event: impl FnMut(E),
+) -> Result<R, ApiError> {
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let handle = tokio::task::spawn_blocking(move || {
+        work(&mut |event| {
+            let _ = tx.send(event);
+        })
+    });
+    while let Some(event) = rx.recv().await {
+        on_event(event);
+    }
+    handle
+        .await
+        .map_err(|e| ApiError::new(500, format!("后台任务失败: {}", e)))
+}
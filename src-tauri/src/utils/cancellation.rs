@@ -0,0 +1,75 @@
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+
+use once_cell::sync::Lazy;
+
+// 正在运行的可取消任务，键为前端传入的任务id
+static CANCEL_FLAGS: Lazy<Mutex<HashMap<String, Arc<AtomicBool>>>> =
+    Lazy::new(|| Mutex::new(HashMap::new()));
+
+/// 可取消任务的取消令牌，离开作用域时自动从登记表中移除
+pub struct CancelToken {
+    id: String,
+    flag: Arc<AtomicBool>,
+    // 只有register返回的令牌负责注销，克隆出的令牌只共享取消状态
+    registered: bool,
+}
+
+/// 用于把令牌交给阻塞线程，克隆的生命周期不影响登记
+impl Clone for CancelToken {
+    fn clone(&self) -> Self {
+        Self {
+            id: self.id.clone(),
+            flag: self.flag.clone(),
+            registered: false,
+        }
+    }
+}
+
+impl CancelToken {
+    /// 以任务id登记一个新的令牌，同id的旧令牌会被覆盖
+    pub fn register(id: &str) -> Self {
+        let flag = Arc::new(AtomicBool::new(false));
+        CANCEL_FLAGS
+            .lock()
+            .unwrap()
+            .insert(id.to_string(), flag.clone());
+        Self {
+            id: id.to_string(),
+            flag,
+            registered: true,
+        }
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.flag.load(Ordering::Relaxed)
+    }
+}
+
+impl Drop for CancelToken {
+    fn drop(&mut self) {
+        if !self.registered {
+            return;
+        }
+        let mut flags = CANCEL_FLAGS.lock().unwrap();
+        // 只移除自己登记的那一份，避免误删同id的新任务
+        if flags
+            .get(&self.id)
+            .is_some_and(|flag| Arc::ptr_eq(flag, &self.flag))
+        {
+            flags.remove(&self.id);
+        }
+    }
+}
+
+/// 取消指定id的任务，任务不存在时返回false
+pub fn cancel(id: &str) -> bool {
+    match CANCEL_FLAGS.lock().unwrap().get(id) {
+        Some(flag) => {
+            flag.store(true, Ordering::Relaxed);
+            true
+        }
+        None => false,
+    }
+}
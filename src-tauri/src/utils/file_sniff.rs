@@ -0,0 +1,198 @@
+use std::{
+    fs::File,
+    io::Read,
+    path::{Path, PathBuf},
+    sync::Mutex,
+    time::SystemTime,
+};
+
+use once_cell::sync::Lazy;
+
+use crate::{models::files::FileType, utils::lru_cache::LruCache};
+
+// tar的魔数位于257字节处，读取512字节足够覆盖所有签名
+const SNIFF_LEN: usize = 512;
+const MPEG_TS_PACKET_LEN: usize = 188;
+const SNIFF_CACHE_SIZE: usize = 2000;
+
+type SniffKey = (PathBuf, SystemTime, u64);
+
+// 识别结果按路径、修改时间和大小缓存，反复列出同一目录时不必每次都打开文件
+static SNIFF_CACHE: Lazy<Mutex<LruCache<SniffKey, Option<FileType>>>> =
+    Lazy::new(|| Mutex::new(LruCache::new(SNIFF_CACHE_SIZE)));
+
+/// 读取文件头部判断文件类型，无法判断或读取失败时返回None
+pub fn sniff_file_type(path: &Path) -> Option<FileType> {
+    let metadata = path.metadata().ok()?;
+    let Ok(modified) = metadata.modified() else {
+        return read_file_type(path);
+    };
+    let key = (path.to_path_buf(), modified, metadata.len());
+    if let Some(file_type) = SNIFF_CACHE.lock().unwrap().get(&key) {
+        return file_type;
+    }
+    let file_type = read_file_type(path);
+    SNIFF_CACHE.lock().unwrap().put(key, file_type);
+    file_type
+}
+
+fn read_file_type(path: &Path) -> Option<FileType> {
+    let mut file = File::open(path).ok()?;
+    let mut buf = Vec::with_capacity(SNIFF_LEN);
+    file.by_ref()
+        .take(SNIFF_LEN as u64)
+        .read_to_end(&mut buf)
+        .ok()?;
+    sniff_bytes(&buf)
+}
+
+/// 按常见格式的魔数判断类型，都不匹配时不含NUL的UTF-8内容视为文本
+fn sniff_bytes(buf: &[u8]) -> Option<FileType> {
+    if buf.is_empty() {
+        return None;
+    }
+
+    let starts_with = |magic: &[u8]| buf.starts_with(magic);
+    let at = |offset: usize, magic: &[u8]| buf.get(offset..offset + magic.len()) == Some(magic);
+
+    if starts_with(b"\x89PNG\r\n\x1a\n")
+        || starts_with(b"\xFF\xD8\xFF")
+        || starts_with(b"GIF87a")
+        || starts_with(b"GIF89a")
+        || starts_with(b"II*\0")
+        || starts_with(b"MM\0*")
+        || (starts_with(b"RIFF") && at(8, b"WEBP"))
+    {
+        return Some(FileType::Image);
+    }
+
+    // ISO BMFF容器（mp4/mov/heic/avif/m4a）按ftyp中的主品牌区分
+    if at(4, b"ftyp") {
+        return match buf.get(8..12) {
+            Some(b"avif" | b"avis" | b"heic" | b"heix" | b"mif1" | b"msf1") => {
+                Some(FileType::Image)
+            }
+            Some(b"M4A " | b"M4B ") => Some(FileType::Audio),
+            _ => Some(FileType::Video),
+        };
+    }
+
+    if starts_with(b"\x1A\x45\xDF\xA3")
+        || starts_with(b"FLV")
+        || starts_with(b"\x30\x26\xB2\x75\x8E\x66\xCF\x11")
+        || (starts_with(b"RIFF") && at(8, b"AVI "))
+        || (buf[0] == 0x47 && buf.get(MPEG_TS_PACKET_LEN) == Some(&0x47))
+    {
+        return Some(FileType::Video);
+    }
+
+    if starts_with(b"ID3")
+        || starts_with(b"fLaC")
+        || starts_with(b"OggS")
+        || (starts_with(b"RIFF") && at(8, b"WAVE"))
+        || matches!(buf, [0xFF, 0xFB | 0xF3 | 0xF2 | 0xF1 | 0xF9, ..])
+    {
+        return Some(FileType::Audio);
+    }
+
+    if starts_with(b"%PDF-") || starts_with(b"\xD0\xCF\x11\xE0\xA1\xB1\x1A\xE1") {
+        return Some(FileType::Document);
+    }
+
+    if starts_with(b"PK\x03\x04")
+        || starts_with(b"Rar!\x1A\x07")
+        || starts_with(b"7z\xBC\xAF\x27\x1C")
+        || starts_with(b"\x1F\x8B")
+        || starts_with(b"BZh")
+        || starts_with(b"\xFD7zXZ\0")
+        || starts_with(b"\x28\xB5\x2F\xFD")
+        || at(257, b"ustar")
+    {
+        return Some(FileType::Archive);
+    }
+
+    if is_text(buf) {
+        return Some(FileType::Text);
+    }
+
+    None
+}
+
+/// 不含NUL且是合法UTF-8，末尾被截断的多字节字符不影响判断
+fn is_text(buf: &[u8]) -> bool {
+    if buf.contains(&0) {
+        return false;
+    }
+    match std::str::from_utf8(buf) {
+        Ok(_) => true,
+        Err(e) => e.error_len().is_none(),
+    }
+}
+
+/// 按扩展名推断MIME类型，未知扩展名按文件类型退化为通用值
+pub fn mime_type(path: &Path, file_type: FileType) -> &'static str {
+    let extension = path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_lowercase());
+    let by_extension = match extension.as_deref() {
+        Some("png") => Some("image/png"),
+        Some("jpg" | "jpeg") => Some("image/jpeg"),
+        Some("gif") => Some("image/gif"),
+        Some("bmp") => Some("image/bmp"),
+        Some("svg") => Some("image/svg+xml"),
+        Some("webp") => Some("image/webp"),
+        Some("avif") => Some("image/avif"),
+        Some("heic") => Some("image/heic"),
+        Some("heif") => Some("image/heif"),
+        Some("tif" | "tiff") => Some("image/tiff"),
+        Some("ico") => Some("image/x-icon"),
+        Some("mp4" | "m4v") => Some("video/mp4"),
+        Some("mkv") => Some("video/x-matroska"),
+        Some("webm") => Some("video/webm"),
+        Some("avi") => Some("video/x-msvideo"),
+        Some("mov") => Some("video/quicktime"),
+        Some("wmv") => Some("video/x-ms-wmv"),
+        Some("flv") => Some("video/x-flv"),
+        Some("3gp") => Some("video/3gpp"),
+        Some("mpg" | "mpeg") => Some("video/mpeg"),
+        Some("ts" | "m2ts" | "mts") if file_type == FileType::Video => Some("video/mp2t"),
+        Some("mp3") => Some("audio/mpeg"),
+        Some("flac") => Some("audio/flac"),
+        Some("wav") => Some("audio/wav"),
+        Some("aac") => Some("audio/aac"),
+        Some("m4a") => Some("audio/mp4"),
+        Some("ogg") => Some("audio/ogg"),
+        Some("opus") => Some("audio/opus"),
+        Some("txt" | "log" | "ini") => Some("text/plain"),
+        Some("md") => Some("text/markdown"),
+        Some("json") => Some("application/json"),
+        Some("xml") => Some("application/xml"),
+        Some("csv") => Some("text/csv"),
+        Some("vtt") => Some("text/vtt"),
+        Some("pdf") => Some("application/pdf"),
+        Some("doc") => Some("application/msword"),
+        Some("docx") => {
+            Some("application/vnd.openxmlformats-officedocument.wordprocessingml.document")
+        }
+        Some("xls") => Some("application/vnd.ms-excel"),
+        Some("xlsx") => Some("application/vnd.openxmlformats-officedocument.spreadsheetml.sheet"),
+        Some("ppt") => Some("application/vnd.ms-powerpoint"),
+        Some("pptx") => {
+            Some("application/vnd.openxmlformats-officedocument.presentationml.presentation")
+        }
+        Some("epub") => Some("application/epub+zip"),
+        Some("zip") => Some("application/zip"),
+        Some("rar") => Some("application/vnd.rar"),
+        Some("7z") => Some("application/x-7z-compressed"),
+        Some("tar") => Some("application/x-tar"),
+        Some("gz" | "tgz") => Some("application/gzip"),
+        _ => None,
+    };
+
+    by_extension.unwrap_or(match file_type {
+        FileType::Folder => "inode/directory",
+        FileType::Text => "text/plain",
+        _ => "application/octet-stream",
+    })
+}
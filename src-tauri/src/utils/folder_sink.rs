@@ -0,0 +1,205 @@
+use std::{
+    fs::{self, File},
+    io::{BufWriter, ErrorKind, Write},
+    path::{Path, PathBuf},
+};
+
+use zip::{write::SimpleFileOptions, CompressionMethod, ZipWriter};
+
+use crate::{
+    models::{error::ApiError, files::FolderDownloadMode},
+    repos::offline::file_ops,
+};
+
+/// 目录下载中的一项，relative为相对于下载根目录、以`/`分隔的路径
+pub enum FolderEntry {
+    Directory(String),
+    /// source在线时为服务端的虚拟路径，离线时为磁盘上的真实路径
+    File {
+        source: String,
+        relative: String,
+        size: u64,
+    },
+}
+
+/// 目录下载的写入端，打包为zip或按原结构写入下载目录
+pub enum FolderSink {
+    Zip {
+        writer: Box<ZipWriter<BufWriter<File>>>,
+        part_path: PathBuf,
+        target: PathBuf,
+    },
+    Folder {
+        root: PathBuf,
+        current: Option<BufWriter<File>>,
+    },
+}
+
+impl FolderSink {
+    /// 已有同名的压缩包或目录时改用`name (1)`的形式，不覆盖也不合并
+    pub fn create(name: &str, mode: FolderDownloadMode) -> Result<Self, ApiError> {
+        match mode {
+            FolderDownloadMode::Zip => {
+                let (target, part_path) = zip_paths(name)?;
+                let file = File::create(&part_path)
+                    .map_err(|e| ApiError::new(500, format!("创建临时文件失败: {}", e)))?;
+                Ok(FolderSink::Zip {
+                    writer: Box::new(ZipWriter::new(BufWriter::new(file))),
+                    part_path,
+                    target,
+                })
+            }
+            FolderDownloadMode::Folder => {
+                let root = download_target(name)?;
+                Ok(FolderSink::Folder {
+                    root: create_unique_dir(root)?,
+                    current: None,
+                })
+            }
+        }
+    }
+
+    pub fn add_directory(&mut self, relative: &str) -> Result<(), ApiError> {
+        match self {
+            FolderSink::Zip { writer, .. } => writer
+                .add_directory(sanitize(relative), Self::zip_options(0))
+                .map_err(|e| ApiError::new(500, format!("写入压缩包失败: {}", e))),
+            FolderSink::Folder { root, .. } => fs::create_dir_all(root.join(sanitize(relative)))
+                .map_err(|e| ApiError::new(500, format!("创建目录失败: {}", e))),
+        }
+    }
+
+    /// 开始写入新文件，之后的write都写入这个文件
+    pub fn start_file(&mut self, relative: &str, size: u64) -> Result<(), ApiError> {
+        match self {
+            FolderSink::Zip { writer, .. } => writer
+                .start_file(sanitize(relative), Self::zip_options(size))
+                .map_err(|e| ApiError::new(500, format!("写入压缩包失败: {}", e))),
+            FolderSink::Folder { root, current } => {
+                Self::flush_current(current)?;
+                let target = root.join(sanitize(relative));
+                if let Some(parent) = target.parent() {
+                    fs::create_dir_all(parent)
+                        .map_err(|e| ApiError::new(500, format!("创建目录失败: {}", e)))?;
+                }
+                let file = File::create(&target)
+                    .map_err(|e| ApiError::new(500, format!("创建文件失败: {}", e)))?;
+                *current = Some(BufWriter::new(file));
+                Ok(())
+            }
+        }
+    }
+
+    pub fn write(&mut self, data: &[u8]) -> Result<(), ApiError> {
+        let result = match self {
+            FolderSink::Zip { writer, .. } => writer.write_all(data),
+            FolderSink::Folder { current, .. } => match current {
+                Some(file) => file.write_all(data),
+                None => return Err(ApiError::new(500, "没有正在写入的文件".to_string())),
+            },
+        };
+        result.map_err(|e| ApiError::new(500, format!("写入文件失败: {}", e)))
+    }
+
+    /// 完成写入，返回保存位置
+    pub fn finish(self) -> Result<PathBuf, ApiError> {
+        match self {
+            FolderSink::Zip {
+                writer,
+                part_path,
+                target,
+            } => {
+                writer
+                    .finish()
+                    .map_err(|e| ApiError::new(500, format!("写入压缩包失败: {}", e)))?
+                    .flush()
+                    .map_err(|e| ApiError::new(500, format!("写入压缩包失败: {}", e)))?;
+                finish_part(&part_path, target)
+            }
+            FolderSink::Folder { root, mut current } => {
+                Self::flush_current(&mut current)?;
+                Ok(root)
+            }
+        }
+    }
+
+    /// 失败或取消时删除未完成的压缩包，按目录保存时保留已经下载的文件
+    pub fn discard(self) {
+        if let FolderSink::Zip {
+            writer, part_path, ..
+        } = self
+        {
+            drop(writer);
+            let _ = fs::remove_file(part_path);
+        }
+    }
+
+    fn flush_current(current: &mut Option<BufWriter<File>>) -> Result<(), ApiError> {
+        if let Some(mut file) = current.take() {
+            file.flush()
+                .map_err(|e| ApiError::new(500, format!("写入文件失败: {}", e)))?;
+        }
+        Ok(())
+    }
+
+    fn zip_options(size: u64) -> SimpleFileOptions {
+        SimpleFileOptions::default()
+            .compression_method(CompressionMethod::Deflated)
+            .large_file(size >= u32::MAX as u64)
+    }
+}
+
+/// 下载目录下的保存位置，name必须是单独的文件名
+fn download_target(name: &str) -> Result<PathBuf, ApiError> {
+    if name.is_empty() || name == "." || name == ".." || name.contains(['/', '\\']) {
+        return Err(ApiError::new(400, "无效的保存名称".to_string()));
+    }
+    let download_dir =
+        dirs::download_dir().ok_or_else(|| ApiError::new(500, "无法获取下载目录".to_string()))?;
+    Ok(download_dir.join(name))
+}
+
+/// 打包下载的保存位置和写入时使用的临时文件，同名下载同时进行时临时文件也不会冲突
+pub fn zip_paths(name: &str) -> Result<(PathBuf, PathBuf), ApiError> {
+    let target = download_target(&format!("{}.zip", name))?;
+    let part_path = file_ops::sibling_temp_path(&target, "part");
+    Ok((file_ops::available_path(target), part_path))
+}
+
+/// 把写完的临时文件移到保存位置，期间出现了同名文件时改用下一个可用的名字
+pub fn finish_part(part_path: &Path, target: PathBuf) -> Result<PathBuf, ApiError> {
+    let target = file_ops::available_path(target);
+    fs::rename(part_path, &target)
+        .map_err(|e| ApiError::new(500, format!("保存文件失败: {}", e)))?;
+    Ok(target)
+}
+
+fn create_unique_dir(path: PathBuf) -> Result<PathBuf, ApiError> {
+    loop {
+        let candidate = file_ops::available_path(path.clone());
+        match fs::create_dir(&candidate) {
+            Ok(()) => return Ok(candidate),
+            // 检查之后被其他下载抢先创建了，换下一个名字
+            Err(e) if e.kind() == ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(ApiError::new(500, format!("创建目录失败: {}", e))),
+        }
+    }
+}
+
+/// 去掉`.`和`..`等路径成分，防止服务端返回的文件名写到下载目录之外
+fn sanitize(relative: &str) -> String {
+    relative
+        .split(['/', '\\'])
+        .filter(|part| !part.is_empty() && *part != "." && *part != "..")
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// 把下载根目录下的相对路径和名字拼接起来
+pub fn join_relative(relative: &str, name: &str) -> String {
+    if relative.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}/{}", relative, name)
+    }
+}
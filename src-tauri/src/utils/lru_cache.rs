@@ -71,16 +71,22 @@ where
         self.max_size
     }
 
-    /// 移除最旧的缓存条目
-    fn evict_oldest(&mut self) {
-        if let Some((oldest_key, _)) = self
+    /// 修改最大容量，超出的最旧条目会被立即移除
+    pub fn set_max_size(&mut self, max_size: usize) {
+        self.max_size = max_size;
+        while self.cache.len() > max_size {
+            self.evict_oldest();
+        }
+    }
+
+    /// 移除最旧的缓存条目，返回被移除的值
+    fn evict_oldest(&mut self) -> Option<V> {
+        let oldest_key = self
             .cache
             .iter()
             .min_by_key(|(_, entry)| entry.access_time)
-            .map(|(k, v)| (k.clone(), v.access_time))
-        {
-            self.cache.remove(&oldest_key);
-        }
+            .map(|(k, _)| k.clone())?;
+        self.cache.remove(&oldest_key).map(|entry| entry.value)
     }
 }
 
@@ -93,4 +99,15 @@ where
     pub fn memory_usage(&self) -> usize {
         self.cache.values().map(|entry| entry.value.len()).sum()
     }
+
+    /// 按最久未访问的顺序移除条目，直到总字节数不超过max_bytes
+    pub fn shrink_to_bytes(&mut self, max_bytes: usize) {
+        let mut usage = self.memory_usage();
+        while usage > max_bytes {
+            let Some(value) = self.evict_oldest() else {
+                break;
+            };
+            usage -= value.len();
+        }
+    }
 }
@@ -1,2 +1,9 @@
+pub mod blocking;
+pub mod cancellation;
+pub mod file_sniff;
+pub mod folder_sink;
 pub mod lru_cache;
 mod macros;
+pub mod natural_order;
+pub mod tag_expr;
+pub mod text_decode;
@@ -0,0 +1,252 @@
+use std::{collections::HashSet, iter::Peekable, str::Chars};
+
+use crate::models::error::ApiError;
+
+/// 标签表达式，如`4K AND (to-review OR NOT done)`
+///
+/// 运算符不区分大小写，优先级为NOT > AND > OR；相邻的两个标签之间省略运算符时按AND处理，
+/// 含空格或括号的标签名需要用双引号括起来。
+#[derive(Debug, Clone, PartialEq)]
+pub enum TagExpr {
+    Tag(String),
+    Not(Box<TagExpr>),
+    And(Box<TagExpr>, Box<TagExpr>),
+    Or(Box<TagExpr>, Box<TagExpr>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    And,
+    Or,
+    Not,
+    LeftParen,
+    RightParen,
+}
+
+impl TagExpr {
+    pub fn parse(input: &str) -> Result<Self, ApiError> {
+        let tokens = tokenize(input)?;
+        if tokens.is_empty() {
+            return Err(invalid("表达式为空"));
+        }
+        let mut parser = Parser { tokens, pos: 0 };
+        let expr = parser.parse_or()?;
+        if parser.pos < parser.tokens.len() {
+            return Err(invalid("存在多余的右括号"));
+        }
+        Ok(expr)
+    }
+
+    /// tags需要是小写形式，标签比较不区分大小写
+    pub fn matches(&self, tags: &HashSet<String>) -> bool {
+        match self {
+            TagExpr::Tag(tag) => tags.contains(tag),
+            TagExpr::Not(inner) => !inner.matches(tags),
+            TagExpr::And(left, right) => left.matches(tags) && right.matches(tags),
+            TagExpr::Or(left, right) => left.matches(tags) || right.matches(tags),
+        }
+    }
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn parse_or(&mut self) -> Result<TagExpr, ApiError> {
+        let mut expr = self.parse_and()?;
+        while self.peek() == Some(&Token::Or) {
+            self.pos += 1;
+            expr = TagExpr::Or(Box::new(expr), Box::new(self.parse_and()?));
+        }
+        Ok(expr)
+    }
+
+    fn parse_and(&mut self) -> Result<TagExpr, ApiError> {
+        let mut expr = self.parse_unary()?;
+        loop {
+            match self.peek() {
+                Some(Token::And) => self.pos += 1,
+                // 省略运算符的隐式AND
+                Some(Token::Word(_) | Token::Not | Token::LeftParen) => {}
+                _ => break,
+            }
+            expr = TagExpr::And(Box::new(expr), Box::new(self.parse_unary()?));
+        }
+        Ok(expr)
+    }
+
+    fn parse_unary(&mut self) -> Result<TagExpr, ApiError> {
+        let token = self.peek().cloned();
+        self.pos += 1;
+        match token {
+            Some(Token::Not) => Ok(TagExpr::Not(Box::new(self.parse_unary()?))),
+            Some(Token::LeftParen) => {
+                let expr = self.parse_or()?;
+                if self.peek() != Some(&Token::RightParen) {
+                    return Err(invalid("缺少右括号"));
+                }
+                self.pos += 1;
+                Ok(expr)
+            }
+            Some(Token::Word(tag)) => Ok(TagExpr::Tag(tag.to_lowercase())),
+            Some(_) => Err(invalid("运算符缺少操作数")),
+            None => Err(invalid("表达式不完整")),
+        }
+    }
+}
+
+fn tokenize(input: &str) -> Result<Vec<Token>, ApiError> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            c if c.is_whitespace() => {
+                chars.next();
+            }
+            '(' => {
+                chars.next();
+                tokens.push(Token::LeftParen);
+            }
+            ')' => {
+                chars.next();
+                tokens.push(Token::RightParen);
+            }
+            '!' => {
+                chars.next();
+                tokens.push(Token::Not);
+            }
+            '"' => {
+                chars.next();
+                tokens.push(Token::Word(read_quoted(&mut chars)?));
+            }
+            _ => {
+                let mut word = String::new();
+                while let Some(&c) = chars.peek() {
+                    if c.is_whitespace() || matches!(c, '(' | ')' | '"') {
+                        break;
+                    }
+                    word.push(c);
+                    chars.next();
+                }
+                tokens.push(match word.to_uppercase().as_str() {
+                    "AND" => Token::And,
+                    "OR" => Token::Or,
+                    "NOT" => Token::Not,
+                    _ => Token::Word(word),
+                });
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+fn read_quoted(chars: &mut Peekable<Chars>) -> Result<String, ApiError> {
+    let mut word = String::new();
+    for c in chars.by_ref() {
+        if c == '"' {
+            return Ok(word);
+        }
+        word.push(c);
+    }
+    Err(invalid("引号没有闭合"))
+}
+
+fn invalid(reason: &str) -> ApiError {
+    ApiError::new(400, format!("标签表达式无效: {}", reason))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tag(name: &str) -> Box<TagExpr> {
+        Box::new(TagExpr::Tag(name.to_string()))
+    }
+
+    fn tags(names: &[&str]) -> HashSet<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn not_binds_tighter_than_and_and_and_tighter_than_or() {
+        assert_eq!(
+            TagExpr::parse("a OR NOT b AND c").unwrap(),
+            TagExpr::Or(
+                tag("a"),
+                Box::new(TagExpr::And(Box::new(TagExpr::Not(tag("b"))), tag("c")))
+            )
+        );
+    }
+
+    #[test]
+    fn parentheses_override_precedence() {
+        assert_eq!(
+            TagExpr::parse("(a OR b) AND c").unwrap(),
+            TagExpr::And(Box::new(TagExpr::Or(tag("a"), tag("b"))), tag("c"))
+        );
+    }
+
+    #[test]
+    fn adjacent_tags_are_joined_with_and() {
+        assert_eq!(
+            TagExpr::parse("a b OR c").unwrap(),
+            TagExpr::Or(Box::new(TagExpr::And(tag("a"), tag("b"))), tag("c"))
+        );
+        assert_eq!(
+            TagExpr::parse("a !b").unwrap(),
+            TagExpr::And(tag("a"), Box::new(TagExpr::Not(tag("b"))))
+        );
+    }
+
+    #[test]
+    fn operators_and_tags_are_case_insensitive() {
+        let expr = TagExpr::parse("4K and not Done").unwrap();
+        assert_eq!(
+            expr,
+            TagExpr::And(tag("4k"), Box::new(TagExpr::Not(tag("done"))))
+        );
+        assert!(expr.matches(&tags(&["4k"])));
+        assert!(!expr.matches(&tags(&["4k", "done"])));
+    }
+
+    #[test]
+    fn quoted_tags_keep_spaces_parentheses_and_keywords() {
+        assert_eq!(
+            TagExpr::parse(r#""to review" OR "(draft)" OR "and""#).unwrap(),
+            TagExpr::Or(
+                Box::new(TagExpr::Or(tag("to review"), tag("(draft)"))),
+                tag("and")
+            )
+        );
+    }
+
+    #[test]
+    fn unclosed_quote_is_rejected() {
+        assert_eq!(
+            TagExpr::parse(r#""to review"#).unwrap_err().status_code,
+            400
+        );
+    }
+
+    #[test]
+    fn unbalanced_parentheses_are_rejected() {
+        assert!(TagExpr::parse("(a OR b").is_err());
+        assert!(TagExpr::parse("a OR b)").is_err());
+        assert!(TagExpr::parse("()").is_err());
+    }
+
+    #[test]
+    fn incomplete_expressions_are_rejected() {
+        assert!(TagExpr::parse("").is_err());
+        assert!(TagExpr::parse("a AND").is_err());
+        assert!(TagExpr::parse("OR a").is_err());
+        assert!(TagExpr::parse("NOT").is_err());
+    }
+}
@@ -0,0 +1,57 @@
+use chardetng::EncodingDetector;
+use encoding_rs::{Encoding, UTF_8};
+
+// 判断是否为二进制时只看开头这么多字节
+const BINARY_CHECK_LEN: usize = 8 * 1024;
+
+/// 带BOM的内容视为文本；否则出现NUL或控制字符过多时视为二进制
+pub fn is_binary(data: &[u8]) -> bool {
+    if Encoding::for_bom(data).is_some() {
+        return false;
+    }
+    let sample = &data[..data.len().min(BINARY_CHECK_LEN)];
+    if sample.contains(&0) {
+        return true;
+    }
+    let control_count = sample
+        .iter()
+        .filter(|&&b| b < 0x20 && !matches!(b, b'\t' | b'\n' | b'\r' | 0x0c | 0x1b))
+        .count();
+    control_count * 10 > sample.len()
+}
+
+/// 按BOM、UTF-8校验、编码探测的顺序确定编码后解码；forced不为空时直接使用指定编码。
+/// truncated为true时末尾可能截断了多字节字符，解码出的替换字符会被去掉
+pub fn decode(
+    data: &[u8],
+    truncated: bool,
+    forced: Option<&'static Encoding>,
+) -> (String, &'static Encoding) {
+    let encoding = forced.unwrap_or_else(|| detect_encoding(data, truncated));
+    let (content, _, _) = encoding.decode(data);
+    let mut content = content.into_owned();
+    if truncated {
+        while content.ends_with('\u{FFFD}') {
+            content.pop();
+        }
+    }
+    (content, encoding)
+}
+
+fn detect_encoding(data: &[u8], truncated: bool) -> &'static Encoding {
+    if let Some((encoding, _)) = Encoding::for_bom(data) {
+        return encoding;
+    }
+    let is_utf8 = match std::str::from_utf8(data) {
+        Ok(_) => true,
+        Err(e) => truncated && e.error_len().is_none(),
+    };
+    if is_utf8 {
+        return UTF_8;
+    }
+
+    // 资料库里常见GBK/GB18030编码的文本，用cn作为提示提高中文编码的判断优先级
+    let mut detector = EncodingDetector::new();
+    detector.feed(data, !truncated);
+    detector.guess(Some(b"cn"), false)
+}
@@ -0,0 +1,18 @@
+import { AuthStatus, LoginRequest } from '@/types/auth'
+import { invoke } from '@tauri-apps/api/core'
+
+class AuthService {
+  async login(request: LoginRequest): Promise<AuthStatus> {
+    return await invoke<AuthStatus>('login', { request })
+  }
+
+  async logout(): Promise<void> {
+    await invoke('logout')
+  }
+
+  async getAuthStatus(): Promise<AuthStatus> {
+    return await invoke<AuthStatus>('get_auth_status')
+  }
+}
+
+export const authService = new AuthService()
@@ -30,6 +30,10 @@ class FavoriteService {
     return await invoke<FavoriteFileDto[]>('get_all_favorite_files')
   }
 
+  async getFavoritesForPath(path: string): Promise<FavoriteDto[]> {
+    return await invoke<FavoriteDto[]>('get_favorites_for_path', { path })
+  }
+
   async deleteFavoriteFile(id: number): Promise<boolean> {
     return await invoke<boolean>('delete_favorite_file', { id })
   }
@@ -1,9 +1,14 @@
-import { FileInfo } from '@/types/files'
+import { FileInfo, FileListing, ListOptions } from '@/types/files'
 import { invoke } from '@tauri-apps/api/core'
 
 class FileService {
   async getFiles(path: string): Promise<FileInfo[]> {
-    return await invoke<FileInfo[]>('get_files', { path })
+    const listing = await this.listFiles(path)
+    return listing.files
+  }
+
+  async listFiles(path: string, options?: ListOptions): Promise<FileListing> {
+    return await invoke<FileListing>('get_files', { path, options })
   }
 
   async deleteFile(path: string): Promise<void> {
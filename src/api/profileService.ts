@@ -0,0 +1,27 @@
+import { ProfileRequest, ServerProfile } from '@/types/profile'
+import { invoke } from '@tauri-apps/api/core'
+
+class ProfileService {
+  async listProfiles(): Promise<ServerProfile[]> {
+    return await invoke<ServerProfile[]>('list_profiles')
+  }
+
+  async createProfile(request: ProfileRequest): Promise<ServerProfile> {
+    return await invoke<ServerProfile>('create_profile', { request })
+  }
+
+  async updateProfile(id: string, request: ProfileRequest): Promise<ServerProfile> {
+    return await invoke<ServerProfile>('update_profile', { id, request })
+  }
+
+  async deleteProfile(id: string): Promise<void> {
+    await invoke('delete_profile', { id })
+  }
+
+  // 切换成功后后端会推送 profile-changed 事件
+  async activateProfile(id: string): Promise<ServerProfile> {
+    return await invoke<ServerProfile>('activate_profile', { id })
+  }
+}
+
+export const profileService = new ProfileService()
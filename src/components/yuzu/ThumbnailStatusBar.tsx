@@ -116,7 +116,9 @@ export const YuzuThumbnailStatusBar: React.FC<YuzuThumbnailStatusBarProps> = ({
                 ? `${Math.round((status.cache_size / (status.cache_size + status.current_processing)) * 100)}%`
                 : 'N/A'}
             </div>
-            <div>内存使用: {formatBytes(status.cache_memory_usage)}</div>
+            <div>
+              内存使用: {formatBytes(status.cache_memory_usage)}/{formatBytes(status.cache_max_bytes)}
+            </div>
           </div>
         </div>
       )}
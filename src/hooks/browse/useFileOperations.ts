@@ -3,6 +3,10 @@ import { useBrowseStore } from '@/stores/browseStore'
 import { FileInfo, FileType } from '@/types/files'
 import { toast } from '@/utils/toast'
 
+// zip/tar/tar.gz 压缩包可以像目录一样浏览
+const isBrowsableArchive = (file: FileInfo) =>
+  file.type === FileType.Archive && /\.(zip|tar|tgz|tar\.gz)$/i.test(file.name)
+
 /**
  * 文件操作相关的Hook
  * 封装了文件点击、导航、下载、删除等操作逻辑
@@ -21,7 +25,7 @@ export const useFileOperations = () => {
 
   // 处理文件点击
   const handleFileClick = (file: FileInfo) => {
-    if (file.isDirectory) {
+    if (file.isDirectory || isBrowsableArchive(file)) {
       setPath([...path, { name: file.name, id: file.name }])
     } else if (file.type === FileType.Image || file.type === FileType.Video) {
       setPreviewItem(file)
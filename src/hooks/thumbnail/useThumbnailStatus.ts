@@ -10,6 +10,7 @@ export interface ThumbnailStatus {
   cache_size: number
   cache_max_size: number
   cache_memory_usage: number
+  cache_max_bytes: number
 }
 
 export const useThumbnailStatus = () => {
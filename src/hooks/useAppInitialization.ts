@@ -4,6 +4,9 @@ import { useConfigStore } from '../stores/configStore'
 import { toast } from '@/utils/toast'
 import { listen, UnlistenFn } from '@tauri-apps/api/event'
 import { configService, AppConfig } from '@/services/configService'
+import { ProfileChangedPayload } from '@/types/profile'
+import { ConnectivityStatus } from '@/types/connectivity'
+import { AuthRequiredPayload } from '@/types/auth'
 
 export const useAppInitialization = () => {
   const [isFirstLaunch, setIsFirstLaunch] = useState<boolean | null>(null)
@@ -25,24 +28,14 @@ export const useAppInitialization = () => {
             setBaseDir(savedConfig.baseDir)
             setOnline(savedConfig.online)
 
-            // 应用配置到后端
-            await invoke('set_app_config', {
-              config: {
-                baseDir: savedConfig.baseDir,
-                serverUrl: savedConfig.serverUrl,
-                online: savedConfig.online,
-              },
-            })
+            // 应用配置到后端，已保存的配置启动时服务器暂时不可用也照常使用
+            await configService.applyToBackend(savedConfig, true)
           }
         } else {
-          // 首次启动，检查平台并设置默认值
-          const platform = await invoke<string>('get_platform')
-
-          // 如果是安卓系统，设置服务器地址为 10.0.2.2
-          if (platform === 'android') {
-            const androidServerUrl = 'http://10.0.2.2:8080'
-            setServerUrl(androidServerUrl)
-          }
+          // 首次启动，使用后端按平台生成的默认值（如安卓下服务器地址为 10.0.2.2）
+          const defaults = await invoke<AppConfig>('get_app_config')
+          setServerUrl(defaults.serverUrl)
+          setBaseDir(defaults.baseDir)
         }
       } catch (error) {
         console.error('应用初始化失败:', error)
@@ -53,6 +46,50 @@ export const useAppInitialization = () => {
     initializeApp()
   }, [setServerUrl, setBaseDir, setOnline])
 
+  // 切换连接配置后同步前端状态
+  useEffect(() => {
+    const unlisten = listen<ProfileChangedPayload>('profile-changed', event => {
+      const { profile } = event.payload
+      if (profile.kind === 'Server') {
+        setServerUrl(profile.location)
+        setOnline(true)
+      } else {
+        setBaseDir(profile.location)
+        setOnline(false)
+      }
+    })
+    return () => {
+      unlisten.then(fn => fn())
+    }
+  }, [setServerUrl, setBaseDir, setOnline])
+
+  // 登录过期且无法自动刷新时提示
+  useEffect(() => {
+    const unlisten = listen<AuthRequiredPayload>('auth-required', event => {
+      toast.warning(`登录已失效，请重新登录 ${event.payload.serverUrl}`)
+    })
+    return () => {
+      unlisten.then(fn => fn())
+    }
+  }, [])
+
+  // 服务器断开或恢复时提示
+  useEffect(() => {
+    const unlisten = listen<ConnectivityStatus>('connectivity-changed', event => {
+      const { reachable, usingMirror } = event.payload
+      if (reachable) {
+        toast.success('服务器连接已恢复')
+      } else if (usingMirror) {
+        toast.warning('服务器不可用，已切换到离线镜像（只读）')
+      } else {
+        toast.warning('服务器不可用')
+      }
+    })
+    return () => {
+      unlisten.then(fn => fn())
+    }
+  }, [])
+
   useEffect(() => {
     let unlisten: UnlistenFn | null = null
 
@@ -73,27 +110,18 @@ export const useAppInitialization = () => {
 
   async function configure(newBaseDir: string, newServerUrl: string, isOnline: boolean) {
     try {
-      // 更新状态
-      setBaseDir(newBaseDir)
-      setServerUrl(newServerUrl)
-      setOnline(isOnline)
-
-      // 保存到 tauri store
       const config: AppConfig = {
         baseDir: newBaseDir,
         serverUrl: newServerUrl,
         online: isOnline,
       }
-      await configService.saveConfig(config)
+      // 后端检查通过后才会保存到 tauri store，不通过时保持原有配置
+      await configService.applyToBackend(config)
 
-      // 发送到后端
-      await invoke('set_app_config', {
-        config: {
-          baseDir: newBaseDir,
-          serverUrl: newServerUrl,
-          online: isOnline,
-        },
-      })
+      // 更新状态
+      setBaseDir(newBaseDir)
+      setServerUrl(newServerUrl)
+      setOnline(isOnline)
 
       toast.success('应用配置已保存')
     } catch (error) {
@@ -1,3 +1,4 @@
+import { invoke } from '@tauri-apps/api/core'
 import { Store } from '@tauri-apps/plugin-store'
 
 export interface AppConfig {
@@ -6,6 +7,22 @@ export interface AppConfig {
   baseDir: string
 }
 
+export type CheckLevel = 'Ok' | 'Warning' | 'Error'
+
+export interface FieldCheck {
+  field: string
+  level: CheckLevel
+  message: string
+}
+
+export interface ConfigCheckResult {
+  valid: boolean
+  checks: FieldCheck[]
+}
+
+// 后端配置中还有前端不关心的字段，应用配置时以后端当前值为基础合并
+type BackendConfig = AppConfig & Record<string, unknown>
+
 const STORE_KEY = 'app-config'
 const store = await Store.load('.settings.dat')
 
@@ -21,14 +38,20 @@ export const configService = {
     }
   },
 
-  async saveConfig(config: AppConfig): Promise<void> {
-    try {
-      await store.set(STORE_KEY, config)
-      await store.save()
-    } catch (error) {
-      console.error('保存配置失败:', error)
-      throw error
-    }
+  // force 为 true 时跳过后端的配置检查
+  async applyToBackend(config: AppConfig, force = false): Promise<void> {
+    const current = await invoke<BackendConfig>('get_app_config')
+    await invoke('set_app_config', {
+      config: { ...current, ...config },
+      force,
+    })
+  },
+
+  async validateConfig(config: AppConfig): Promise<ConfigCheckResult> {
+    const current = await invoke<BackendConfig>('get_app_config')
+    return await invoke<ConfigCheckResult>('validate_app_config', {
+      config: { ...current, ...config },
+    })
   },
 
   async isFirstLaunch(): Promise<boolean> {
@@ -0,0 +1,14 @@
+export interface LoginRequest {
+  username: string
+  password: string
+}
+
+export interface AuthStatus {
+  serverUrl: string
+  loggedIn: boolean
+  username?: string | null
+}
+
+export interface AuthRequiredPayload {
+  serverUrl: string
+}
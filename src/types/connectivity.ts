@@ -0,0 +1,6 @@
+export interface ConnectivityStatus {
+  serverUrl: string
+  reachable: boolean
+  // 只读操作正在使用离线镜像
+  usingMirror: boolean
+}
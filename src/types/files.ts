@@ -5,11 +5,43 @@ export interface FileInfo {
   type: FileType
   lastModified: number
   path: string
+  tags?: string[]
+  favoriteIds?: number[]
 }
 
 export enum FileType {
   Folder = 'Folder',
   Image = 'Image',
   Video = 'Video',
+  Audio = 'Audio',
+  Text = 'Text',
+  Document = 'Document',
+  Archive = 'Archive',
   Other = 'Other',
 }
+
+export enum SortKey {
+  Name = 'Name',
+  Size = 'Size',
+  Modified = 'Modified',
+  Type = 'Type',
+}
+
+export enum SortDirection {
+  Asc = 'Asc',
+  Desc = 'Desc',
+}
+
+export interface ListOptions {
+  sortBy?: SortKey
+  direction?: SortDirection
+  naturalOrder?: boolean
+  fileTypes?: FileType[]
+  offset?: number
+  limit?: number
+}
+
+export interface FileListing {
+  files: FileInfo[]
+  total: number
+}
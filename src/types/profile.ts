@@ -0,0 +1,34 @@
+export type ProfileKind = 'Server' | 'Local'
+
+// 密码只保存在后端的私有文件中，不会返回给前端
+export interface ProfileCredentials {
+  username: string
+  hasPassword: boolean
+}
+
+export interface ProfileCredentialsRequest {
+  username: string
+  // 不填时沿用已保存的密码，空字符串表示删除密码
+  password?: string | null
+}
+
+export interface ServerProfile {
+  id: string
+  name: string
+  kind: ProfileKind
+  // Server 为服务器地址，Local 为本地目录
+  location: string
+  credentials?: ProfileCredentials | null
+}
+
+export interface ProfileRequest {
+  name: string
+  kind: ProfileKind
+  location: string
+  credentials?: ProfileCredentialsRequest | null
+}
+
+export interface ProfileChangedPayload {
+  profile: ServerProfile
+  previousId?: string | null
+}